@@ -1,55 +1,484 @@
-use soroban_sdk::{Env, Address, panic_with_error};
+use soroban_sdk::{panic_with_error, symbol_short, Address, BytesN, Env, MuxedAddress, String};
 
-use crate::{storage, events};
 use crate::error::ContractError;
+use crate::storage::{
+    Attestation, DepositRecord, Operation, Redemption, RedemptionStatus, Role, RoleGrant,
+    TokenMetadata,
+};
+use crate::{events, storage};
 
-pub fn mint(env: Env, to: Address, amount: i128) {
-    if amount <= 0 {
-        panic_with_error!(&env, ContractError::InvalidAmount);
+pub fn init(env: Env, admin: Address, decimal: u32, name: String, symbol: String) {
+    if storage::has_admin(&env) {
+        panic_with_error!(&env, ContractError::AlreadyInitialized);
     }
 
+    storage::set_admin(&env, &admin);
+    storage::set_metadata(
+        &env,
+        &TokenMetadata {
+            decimal,
+            name,
+            symbol,
+        },
+    );
+}
+
+pub fn grant_role(env: Env, role: Role, account: Address, cap: i128, period_ledgers: u32) {
     let admin = storage::get_admin(&env);
     admin.require_auth();
+    storage::extend_instance(&env);
 
-    let bal = storage::get_balance(&env, &to);
-    storage::set_balance(&env, &to, bal + amount);
+    // Roles without a cap ignore whatever was passed for it
+    let (cap, period_ledgers) = if role.is_capped() {
+        if cap <= 0 || period_ledgers == 0 {
+            panic_with_error!(&env, ContractError::InvalidAmount);
+        }
+        (cap, period_ledgers)
+    } else {
+        (0, 0)
+    };
 
-    events::mint(&env, &to, amount);
+    storage::set_role(
+        &env,
+        role,
+        &account,
+        &RoleGrant {
+            cap,
+            period_ledgers,
+            period_start: env.ledger().sequence(),
+            used: 0,
+        },
+    );
+    events::role_granted(&env, role, &account, cap, period_ledgers);
 }
 
-pub fn burn(env: Env, from: Address, amount: i128) {
+pub fn revoke_role(env: Env, role: Role, account: Address) {
+    let admin = storage::get_admin(&env);
+    admin.require_auth();
+    storage::extend_instance(&env);
+
+    storage::remove_role(&env, role, &account);
+    events::role_revoked(&env, role, &account);
+}
+
+pub fn set_supply_ceiling(env: Env, ceiling: i128) {
+    let admin = storage::get_admin(&env);
+    admin.require_auth();
+    storage::extend_instance(&env);
+
+    if ceiling < storage::get_total_supply(&env) {
+        panic_with_error!(&env, ContractError::SupplyCeilingExceeded);
+    }
+
+    storage::set_supply_ceiling(&env, ceiling);
+    events::supply_ceiling(&env, ceiling);
+}
+
+pub fn mint(env: Env, minter: Address, to: Address, amount: i128) {
+    minter.require_auth();
+    storage::extend_instance(&env);
+
+    mint_balance(&env, &minter, &to, amount);
+}
+
+pub fn mint_for_deposit(
+    env: Env,
+    minter: Address,
+    to: Address,
+    amount: i128,
+    reference: BytesN<32>,
+) {
+    minter.require_auth();
+    storage::extend_instance(&env);
+
+    if storage::get_deposit(&env, &reference).is_some() {
+        panic_with_error!(&env, ContractError::DuplicateDeposit);
+    }
+
+    mint_balance(&env, &minter, &to, amount);
+    storage::set_deposit(
+        &env,
+        &reference,
+        &DepositRecord {
+            minter,
+            to: to.clone(),
+            amount,
+            ledger: env.ledger().sequence(),
+        },
+    );
+    events::deposit(&env, &reference, &to, amount);
+}
+
+pub fn burn_by(env: Env, burner: Address, from: Address, amount: i128) {
+    burner.require_auth();
+    storage::extend_instance(&env);
+
     if amount <= 0 {
         panic_with_error!(&env, ContractError::InvalidAmount);
     }
 
+    use_role(&env, Role::Burner, &burner, amount);
+    ensure_not_frozen(&env, &burner);
+    spend_allowance(&env, &from, &burner, amount);
+    burn_balance(&env, &from, amount);
+}
+
+pub fn burn(env: Env, from: Address, amount: i128) {
+    from.require_auth();
+    storage::extend_instance(&env);
+
+    burn_balance(&env, &from, amount);
+}
+
+pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+    spender.require_auth();
+    storage::extend_instance(&env);
+
+    ensure_not_frozen(&env, &spender);
+    spend_allowance(&env, &from, &spender, amount);
+    burn_balance(&env, &from, amount);
+}
+
+pub fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+    from.require_auth();
+    storage::extend_instance(&env);
+
+    move_balance(&env, &from, &to.address(), amount);
+    events::transfer(&env, &from, &to.address(), to.id(), amount);
+}
+
+pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+    spender.require_auth();
+    storage::extend_instance(&env);
+
+    ensure_not_frozen(&env, &spender);
+    spend_allowance(&env, &from, &spender, amount);
+    move_balance(&env, &from, &to, amount);
+    events::transfer(&env, &from, &to, None, amount);
+}
+
+pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+    from.require_auth();
+    storage::extend_instance(&env);
+
+    if amount < 0 {
+        panic_with_error!(&env, ContractError::InvalidAmount);
+    }
+    if amount > 0 && expiration_ledger < env.ledger().sequence() {
+        panic_with_error!(&env, ContractError::InvalidExpirationLedger);
+    }
+
+    storage::set_allowance(&env, &from, &spender, amount, expiration_ledger);
+    events::approve(&env, &from, &spender, amount, expiration_ledger);
+}
+
+pub fn post_attestation(
+    env: Env,
+    auditor: Address,
+    reserve_amount: i128,
+    currency: String,
+    timestamp: u64,
+    report_hash: BytesN<32>,
+) {
+    auditor.require_auth();
+    storage::extend_instance(&env);
+
+    if storage::get_role(&env, Role::Auditor, &auditor).is_none() {
+        panic_with_error!(&env, ContractError::Unauthorized);
+    }
+
+    // Reports must move forward in time and cannot be dated in the future
+    let previous = storage::get_attestation(&env).map_or(0, |a| a.timestamp);
+    if reserve_amount < 0 || timestamp <= previous || timestamp > env.ledger().timestamp() {
+        panic_with_error!(&env, ContractError::InvalidAttestation);
+    }
+
+    let attestation = Attestation {
+        auditor,
+        reserve_amount,
+        currency,
+        timestamp,
+        report_hash,
+    };
+    storage::set_attestation(&env, &attestation);
+    events::attestation(&env, &attestation);
+}
+
+pub fn set_reserve_check(env: Env, enabled: bool) {
     let admin = storage::get_admin(&env);
     admin.require_auth();
+    storage::extend_instance(&env);
 
-    let bal = storage::get_balance(&env, &from);
-    if bal < amount {
-        panic_with_error!(&env, ContractError::InsufficientBalance);
+    storage::set_reserve_check(&env, enabled);
+    events::reserve_check(&env, enabled);
+}
+
+/// Attested reserves over total supply in basis points, if there is anything to compare
+pub fn collateralization_ratio(env: Env) -> Option<i128> {
+    let attestation = storage::get_attestation(&env)?;
+    let total_supply = storage::get_total_supply(&env);
+    if total_supply == 0 {
+        return None;
     }
+    Some(attestation.reserve_amount * 10_000 / total_supply)
+}
+
+pub fn pause(env: Env, caller: Address, op: Operation) {
+    caller.require_auth();
+    storage::extend_instance(&env);
+
+    require_admin_or_role(&env, &caller, Role::Guardian);
+
+    let mut paused = storage::get_paused_ops(&env);
+    paused.set(op, true);
+    storage::set_paused_ops(&env, &paused);
+    events::pause(&env, op, &caller);
+}
+
+pub fn unpause(env: Env, op: Operation) {
+    let admin = storage::get_admin(&env);
+    admin.require_auth();
+    storage::extend_instance(&env);
+
+    let mut paused = storage::get_paused_ops(&env);
+    paused.set(op, false);
+    storage::set_paused_ops(&env, &paused);
+    events::unpause(&env, op);
+}
+
+pub fn freeze(env: Env, caller: Address, account: Address) {
+    caller.require_auth();
+    storage::extend_instance(&env);
+
+    require_admin_or_role(&env, &caller, Role::Compliance);
+    storage::set_frozen(&env, &account, true);
+    events::freeze(&env, &account);
+}
+
+pub fn unfreeze(env: Env, caller: Address, account: Address) {
+    caller.require_auth();
+    storage::extend_instance(&env);
 
-    storage::set_balance(&env, &from, bal - amount);
-    events::burn(&env, &from, amount);
+    require_admin_or_role(&env, &caller, Role::Compliance);
+    storage::set_frozen(&env, &account, false);
+    events::unfreeze(&env, &account);
 }
 
-pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+pub fn clawback(env: Env, caller: Address, from: Address, amount: i128) {
+    caller.require_auth();
+    storage::extend_instance(&env);
+
     if amount <= 0 {
         panic_with_error!(&env, ContractError::InvalidAmount);
     }
 
+    // The admin is not bound by the compliance cap
+    if caller != storage::get_admin(&env) {
+        use_role(&env, Role::Compliance, &caller, amount);
+    }
+
+    // Clawback is how funds leave a frozen account, so it skips the freeze check
+    debit_supply(&env, &from, amount);
+    events::clawback(&env, &from, amount);
+}
+
+pub fn request_redemption(
+    env: Env,
+    from: Address,
+    amount: i128,
+    payout_ref_hash: BytesN<32>,
+) -> u64 {
     from.require_auth();
+    storage::extend_instance(&env);
+
+    let contract = env.current_contract_address();
+    move_balance(&env, &from, &contract, amount);
+    events::transfer(&env, &from, &contract, None, amount);
+
+    let redemption = Redemption {
+        id: storage::next_redemption_id(&env),
+        from,
+        amount,
+        payout_ref_hash,
+        status: RedemptionStatus::Pending,
+        requested_at: env.ledger().timestamp(),
+    };
+    storage::set_redemption(&env, &redemption);
+    events::redemption(&env, symbol_short!("requested"), &redemption);
+
+    redemption.id
+}
+
+pub fn settle_redemption(env: Env, operator: Address, id: u64) {
+    operator.require_auth();
+    storage::extend_instance(&env);
+
+    let mut redemption = pending_redemption(&env, id);
+    use_role(&env, Role::Operator, &operator, redemption.amount);
+
+    // The fiat has been paid out, so the escrowed tokens leave circulation
+    burn_balance(&env, &env.current_contract_address(), redemption.amount);
+
+    redemption.status = RedemptionStatus::Settled;
+    storage::set_redemption(&env, &redemption);
+    events::redemption(&env, symbol_short!("settled"), &redemption);
+}
+
+pub fn reject_redemption(env: Env, operator: Address, id: u64) {
+    operator.require_auth();
+    storage::extend_instance(&env);
+
+    let mut redemption = pending_redemption(&env, id);
+    if storage::get_role(&env, Role::Operator, &operator).is_none() {
+        panic_with_error!(&env, ContractError::Unauthorized);
+    }
 
-    let from_bal = storage::get_balance(&env, &from);
+    let contract = env.current_contract_address();
+    move_balance(&env, &contract, &redemption.from, redemption.amount);
+    events::transfer(&env, &contract, &redemption.from, None, redemption.amount);
+
+    redemption.status = RedemptionStatus::Rejected;
+    storage::set_redemption(&env, &redemption);
+    events::redemption(&env, symbol_short!("rejected"), &redemption);
+}
+
+pub fn migrate_balances(env: Env, limit: u32) -> u32 {
+    let admin = storage::get_admin(&env);
+    admin.require_auth();
+    storage::extend_instance(&env);
+
+    storage::migrate_legacy_balances(&env, limit)
+}
+
+/// Check `caller` is the admin or holds `role`
+fn require_admin_or_role(env: &Env, caller: &Address, role: Role) {
+    if *caller != storage::get_admin(env) && storage::get_role(env, role, caller).is_none() {
+        panic_with_error!(env, ContractError::Unauthorized);
+    }
+}
+
+fn pending_redemption(env: &Env, id: u64) -> Redemption {
+    let Some(redemption) = storage::get_redemption(env, id) else {
+        panic_with_error!(env, ContractError::RedemptionNotFound);
+    };
+    if redemption.status != RedemptionStatus::Pending {
+        panic_with_error!(env, ContractError::RedemptionNotPending);
+    }
+    redemption
+}
+
+fn ensure_not_paused(env: &Env, op: Operation) {
+    if storage::get_paused_ops(env).is_paused(op) {
+        panic_with_error!(env, ContractError::OperationPaused);
+    }
+}
+
+fn ensure_not_frozen(env: &Env, account: &Address) {
+    if storage::is_frozen(env, account) {
+        panic_with_error!(env, ContractError::AccountFrozen);
+    }
+}
+
+/// Check `account` holds `role` and count `amount` against its cap for the current period
+fn use_role(env: &Env, role: Role, account: &Address, amount: i128) {
+    let Some(mut grant) = storage::get_role(env, role, account) else {
+        panic_with_error!(env, ContractError::Unauthorized);
+    };
+
+    let now = env.ledger().sequence();
+    if now >= grant.period_start.saturating_add(grant.period_ledgers) {
+        grant.period_start = now;
+        grant.used = 0;
+    }
+    if grant.used + amount > grant.cap {
+        panic_with_error!(env, ContractError::RoleCapExceeded);
+    }
+
+    grant.used += amount;
+    storage::set_role(env, role, account, &grant);
+}
+
+fn mint_balance(env: &Env, minter: &Address, to: &Address, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    ensure_not_paused(env, Operation::Mint);
+    ensure_not_frozen(env, to);
+    use_role(env, Role::Minter, minter, amount);
+
+    let total_supply = storage::get_total_supply(env) + amount;
+    if storage::get_supply_ceiling(env).is_some_and(|ceiling| total_supply > ceiling) {
+        panic_with_error!(env, ContractError::SupplyCeilingExceeded);
+    }
+    if storage::get_reserve_check(env)
+        && storage::get_attestation(env).is_none_or(|a| total_supply > a.reserve_amount)
+    {
+        panic_with_error!(env, ContractError::ReservesExceeded);
+    }
+    storage::set_total_supply(env, total_supply);
+
+    let bal = storage::get_balance(env, to);
+    storage::set_balance(env, to, bal + amount);
+
+    events::mint(env, to, amount);
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let allowance = storage::get_allowance(env, from, spender);
+    if allowance.amount < amount {
+        panic_with_error!(env, ContractError::InsufficientAllowance);
+    }
+
+    storage::set_allowance(
+        env,
+        from,
+        spender,
+        allowance.amount - amount,
+        allowance.expiration_ledger,
+    );
+}
+
+fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+    ensure_not_paused(env, Operation::Transfer);
+    ensure_not_frozen(env, from);
+    ensure_not_frozen(env, to);
+
+    let from_bal = storage::get_balance(env, from);
     if from_bal < amount {
-        panic_with_error!(&env, ContractError::InsufficientBalance);
+        panic_with_error!(env, ContractError::InsufficientBalance);
     }
 
-    let to_bal = storage::get_balance(&env, &to);
+    storage::set_balance(env, from, from_bal - amount);
+    let to_bal = storage::get_balance(env, to);
+    storage::set_balance(env, to, to_bal + amount);
+}
+
+fn burn_balance(env: &Env, from: &Address, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+    ensure_not_paused(env, Operation::Burn);
+    ensure_not_frozen(env, from);
 
-    storage::set_balance(&env, &from, from_bal - amount);
-    storage::set_balance(&env, &to, to_bal + amount);
+    debit_supply(env, from, amount);
+    events::burn(env, from, amount);
+}
+
+/// Take `amount` out of `from`'s balance and out of circulation
+fn debit_supply(env: &Env, from: &Address, amount: i128) {
+    let bal = storage::get_balance(env, from);
+    if bal < amount {
+        panic_with_error!(env, ContractError::InsufficientBalance);
+    }
 
-    events::transfer(&env, &from, &to, amount);
+    storage::set_balance(env, from, bal - amount);
+    storage::set_total_supply(env, storage::get_total_supply(env) - amount);
 }
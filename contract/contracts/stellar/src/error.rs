@@ -6,4 +6,16 @@ pub enum ContractError {
     Unauthorized = 1,
     InsufficientBalance = 2,
     InvalidAmount = 3,
+    AlreadyInitialized = 4,
+    InsufficientAllowance = 5,
+    InvalidExpirationLedger = 6,
+    RoleCapExceeded = 7,
+    SupplyCeilingExceeded = 8,
+    AccountFrozen = 9,
+    DuplicateDeposit = 10,
+    RedemptionNotFound = 11,
+    RedemptionNotPending = 12,
+    OperationPaused = 13,
+    InvalidAttestation = 14,
+    ReservesExceeded = 15,
 }
@@ -1,34 +1,113 @@
 #![allow(deprecated)]
 
-use soroban_sdk::{Env, Address, symbol_short};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
 
-pub fn transfer(env: &Env, from: &Address, to: &Address, amount: i128) {
-    env.events().publish(
-        (
-            symbol_short!("transfer"),
-            from.clone(),
-            to.clone(),
+use crate::storage::{Attestation, Operation, Redemption, Role};
+
+/// Event data for transfers to a muxed address, as defined by CAP-67
+#[contracttype]
+pub struct MuxedTransferData {
+    pub amount: i128,
+    pub to_muxed_id: u64,
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address, to_muxed_id: Option<u64>, amount: i128) {
+    let topics = (symbol_short!("transfer"), from.clone(), to.clone());
+    match to_muxed_id {
+        Some(to_muxed_id) => env.events().publish(
+            topics,
+            MuxedTransferData {
+                amount,
+                to_muxed_id,
+            },
         ),
-        amount,
+        None => env.events().publish(topics, amount),
+    }
+}
+
+pub fn approve(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) {
+    env.events().publish(
+        (symbol_short!("approve"), from.clone(), spender.clone()),
+        (amount, expiration_ledger),
     );
 }
 
-pub fn mint(env: &Env, to: &Address, amount: i128) {
+pub fn role_granted(env: &Env, role: Role, account: &Address, cap: i128, period_ledgers: u32) {
     env.events().publish(
-        (
-            symbol_short!("mint"),
-            to.clone(),
-        ),
-        amount,
+        (Symbol::new(env, "role_granted"), role, account.clone()),
+        (cap, period_ledgers),
     );
 }
 
-pub fn burn(env: &Env, from: &Address, amount: i128) {
+pub fn role_revoked(env: &Env, role: Role, account: &Address) {
     env.events().publish(
-        (
-            symbol_short!("burn"),
-            from.clone(),
-        ),
-        amount,
+        (Symbol::new(env, "role_revoked"), role, account.clone()),
+        (),
     );
 }
+
+pub fn supply_ceiling(env: &Env, ceiling: i128) {
+    env.events()
+        .publish((Symbol::new(env, "supply_ceiling"),), ceiling);
+}
+
+pub fn attestation(env: &Env, attestation: &Attestation) {
+    env.events().publish(
+        (symbol_short!("attest"), attestation.auditor.clone()),
+        attestation.clone(),
+    );
+}
+
+pub fn reserve_check(env: &Env, enabled: bool) {
+    env.events()
+        .publish((Symbol::new(env, "reserve_check"),), enabled);
+}
+
+pub fn pause(env: &Env, op: Operation, caller: &Address) {
+    env.events()
+        .publish((symbol_short!("pause"), op), caller.clone());
+}
+
+pub fn unpause(env: &Env, op: Operation) {
+    env.events().publish((symbol_short!("unpause"), op), ());
+}
+
+pub fn freeze(env: &Env, account: &Address) {
+    env.events()
+        .publish((symbol_short!("freeze"), account.clone()), ());
+}
+
+pub fn unfreeze(env: &Env, account: &Address) {
+    env.events()
+        .publish((symbol_short!("unfreeze"), account.clone()), ());
+}
+
+pub fn clawback(env: &Env, from: &Address, amount: i128) {
+    env.events()
+        .publish((symbol_short!("clawback"), from.clone()), amount);
+}
+
+pub fn deposit(env: &Env, reference: &BytesN<32>, to: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("deposit"), reference.clone()),
+        (to.clone(), amount),
+    );
+}
+
+// The offramp worker keys on the second topic and reads the full record from the data
+pub fn redemption(env: &Env, action: Symbol, redemption: &Redemption) {
+    env.events().publish(
+        (symbol_short!("redeem"), action, redemption.from.clone()),
+        redemption.clone(),
+    );
+}
+
+pub fn mint(env: &Env, to: &Address, amount: i128) {
+    env.events()
+        .publish((symbol_short!("mint"), to.clone()), amount);
+}
+
+pub fn burn(env: &Env, from: &Address, amount: i128) {
+    env.events()
+        .publish((symbol_short!("burn"), from.clone()), amount);
+}
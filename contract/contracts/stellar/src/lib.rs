@@ -1,47 +1,220 @@
 #![no_std]
 
+mod contract;
+mod error;
+mod events;
 mod storage;
 
-use soroban_sdk::{contract, contractimpl, Address, Env};
+use soroban_sdk::{
+    contract, contractimpl, token::TokenInterface, Address, BytesN, Env, MuxedAddress, String,
+};
+
+pub use crate::storage::{
+    Attestation, DepositRecord, Operation, PausedOps, Redemption, RedemptionStatus, Role, RoleGrant,
+};
 
 #[contract]
 pub struct AfrIContract;
 
 #[contractimpl]
 impl AfrIContract {
-    pub fn init(env: Env, admin: Address) {
-        storage::set_admin(&env, &admin);
+    pub fn init(env: Env, admin: Address, decimal: u32, name: String, symbol: String) {
+        contract::init(env, admin, decimal, name, symbol);
+    }
+
+    // Grants a role with a cap on the amount it can mint or burn every `period_ledgers`;
+    // granting again replaces the cap and starts a new period. Guardian and auditor
+    // grants have no cap and ignore both fields
+    pub fn grant_role(env: Env, role: Role, account: Address, cap: i128, period_ledgers: u32) {
+        contract::grant_role(env, role, account, cap, period_ledgers);
+    }
+
+    pub fn revoke_role(env: Env, role: Role, account: Address) {
+        contract::revoke_role(env, role, account);
+    }
+
+    pub fn role(env: Env, role: Role, account: Address) -> Option<RoleGrant> {
+        storage::get_role(&env, role, &account)
+    }
+
+    pub fn set_supply_ceiling(env: Env, ceiling: i128) {
+        contract::set_supply_ceiling(env, ceiling);
+    }
+
+    pub fn supply_ceiling(env: Env) -> Option<i128> {
+        storage::get_supply_ceiling(&env)
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        storage::get_total_supply(&env)
+    }
+
+    // Mint must be signed by a minter and stays within its cap and the supply ceiling
+    pub fn mint(env: Env, minter: Address, to: Address, amount: i128) {
+        contract::mint(env, minter, to, amount);
+    }
+
+    // Mints for a confirmed fiat deposit at most once per `reference`, the hash of the
+    // payment reference, so retried onramp calls cannot double-mint
+    pub fn mint_for_deposit(
+        env: Env,
+        minter: Address,
+        to: Address,
+        amount: i128,
+        reference: BytesN<32>,
+    ) {
+        contract::mint_for_deposit(env, minter, to, amount, reference);
+    }
+
+    pub fn deposit(env: Env, reference: BytesN<32>) -> Option<DepositRecord> {
+        storage::get_deposit(&env, &reference)
+    }
+
+    // Burns from an account that approved the burner, signed by the burner and within
+    // its cap; spends the allowance like `burn_from`
+    pub fn burn_by(env: Env, burner: Address, from: Address, amount: i128) {
+        contract::burn_by(env, burner, from, amount);
+    }
+
+    // Records an auditor's report of the fiat reserves, replacing the previous one
+    pub fn post_attestation(
+        env: Env,
+        auditor: Address,
+        reserve_amount: i128,
+        currency: String,
+        timestamp: u64,
+        report_hash: BytesN<32>,
+    ) {
+        contract::post_attestation(
+            env,
+            auditor,
+            reserve_amount,
+            currency,
+            timestamp,
+            report_hash,
+        );
+    }
+
+    pub fn latest_attestation(env: Env) -> Option<Attestation> {
+        storage::get_attestation(&env)
+    }
+
+    // Reserves over total supply in basis points; none until there is an attestation
+    // and tokens in circulation
+    pub fn collateralization_ratio(env: Env) -> Option<i128> {
+        contract::collateralization_ratio(env)
+    }
+
+    // When enabled, mints that would take supply above the attested reserves are refused
+    pub fn set_reserve_check(env: Env, enabled: bool) {
+        contract::set_reserve_check(env, enabled);
+    }
+
+    // The admin or a guardian can pause minting, burning or transfers on their own;
+    // clawbacks are never paused
+    pub fn pause(env: Env, caller: Address, op: Operation) {
+        contract::pause(env, caller, op);
+    }
+
+    // Only the admin can resume a paused operation
+    pub fn unpause(env: Env, op: Operation) {
+        contract::unpause(env, op);
+    }
+
+    pub fn paused_ops(env: Env) -> PausedOps {
+        storage::get_paused_ops(&env)
+    }
+
+    // The admin or a compliance role holder can freeze an account; frozen accounts
+    // cannot send, receive, mint or burn
+    pub fn freeze(env: Env, caller: Address, account: Address) {
+        contract::freeze(env, caller, account);
+    }
+
+    pub fn unfreeze(env: Env, caller: Address, account: Address) {
+        contract::unfreeze(env, caller, account);
+    }
+
+    pub fn is_frozen(env: Env, account: Address) -> bool {
+        storage::is_frozen(&env, &account)
+    }
+
+    // Removes funds from any account, frozen or not, and from the total supply
+    pub fn clawback(env: Env, caller: Address, from: Address, amount: i128) {
+        contract::clawback(env, caller, from, amount);
+    }
+
+    // Moves `amount` into the contract until an operator settles the fiat payout
+    // identified by `payout_ref_hash`; returns the redemption id
+    pub fn request_redemption(
+        env: Env,
+        from: Address,
+        amount: i128,
+        payout_ref_hash: BytesN<32>,
+    ) -> u64 {
+        contract::request_redemption(env, from, amount, payout_ref_hash)
+    }
+
+    // Burns the escrowed tokens once the payout has been made
+    pub fn settle_redemption(env: Env, operator: Address, id: u64) {
+        contract::settle_redemption(env, operator, id);
+    }
+
+    // Refunds the escrowed tokens when the payout cannot be made
+    pub fn reject_redemption(env: Env, operator: Address, id: u64) {
+        contract::reject_redemption(env, operator, id);
+    }
+
+    pub fn redemption(env: Env, id: u64) -> Option<Redemption> {
+        storage::get_redemption(&env, id)
+    }
+
+    // Moves up to `limit` holders out of the pre-upgrade balance map; returns how many remain
+    pub fn migrate_balances(env: Env, limit: u32) -> u32 {
+        contract::migrate_balances(env, limit)
+    }
+}
+
+#[contractimpl]
+impl TokenInterface for AfrIContract {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        storage::get_allowance(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        contract::approve(env, from, spender, amount, expiration_ledger);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        storage::get_balance(&env, &id)
+    }
+
+    fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        contract::transfer(env, from, to, amount);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        contract::transfer_from(env, spender, from, to, amount);
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        contract::burn(env, from, amount);
     }
 
-    // Mint now requires the caller address for admin check
-    pub fn mint(env: Env, caller: Address, to: Address, amount: i128) {
-        let admin = storage::get_admin(&env);
-        if caller != admin {
-            panic!("Only admin can mint");
-        }
-        let current = storage::get_balance(&env, &to);
-        storage::set_balance(&env, &to, current + amount);
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        contract::burn_from(env, spender, from, amount);
     }
 
-    pub fn burn(env: Env, from: Address, amount: i128) {
-        let current = storage::get_balance(&env, &from);
-        if amount > current {
-            panic!("Insufficient balance to burn");
-        }
-        storage::set_balance(&env, &from, current - amount);
+    fn decimals(env: Env) -> u32 {
+        storage::get_metadata(&env).decimal
     }
 
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
-        let from_balance = storage::get_balance(&env, &from);
-        if amount > from_balance {
-            panic!("Insufficient balance to transfer");
-        }
-        storage::set_balance(&env, &from, from_balance - amount);
-        storage::set_balance(&env, &to, storage::get_balance(&env, &to) + amount);
+    fn name(env: Env) -> String {
+        storage::get_metadata(&env).name
     }
 
-    pub fn balance(env: Env, user: Address) -> i128 {
-        storage::get_balance(&env, &user)
+    fn symbol(env: Env) -> String {
+        storage::get_metadata(&env).symbol
     }
 }
 
@@ -1,7 +1,171 @@
-use soroban_sdk::{symbol_short, Address, Env, Map, Symbol};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, String, Symbol};
 
 const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
-const BALANCES_KEY: Symbol = symbol_short!("BAL");
+/// Balances of contracts deployed before per-holder storage, drained by `migrate_balances`
+const LEGACY_BALANCES_KEY: Symbol = symbol_short!("BAL");
+const MIGRATED_HOLDERS_KEY: Symbol = symbol_short!("BALMIG");
+/// Set once the legacy balances have been added to the total supply
+const LEGACY_SUPPLY_COUNTED_KEY: Symbol = symbol_short!("BALSUP");
+const METADATA_KEY: Symbol = symbol_short!("META");
+const TOTAL_SUPPLY_KEY: Symbol = symbol_short!("SUPPLY");
+const SUPPLY_CEILING_KEY: Symbol = symbol_short!("CEILING");
+const REDEMPTION_COUNT_KEY: Symbol = symbol_short!("REDCOUNT");
+const PAUSED_OPS_KEY: Symbol = symbol_short!("PAUSED");
+const ATTESTATION_KEY: Symbol = symbol_short!("ATTEST");
+const RESERVE_CHECK_KEY: Symbol = symbol_short!("RSVCHECK");
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+pub const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenMetadata {
+    pub decimal: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Minter,
+    Burner,
+    /// Freezes accounts and claws back funds; the cap limits clawbacks
+    Compliance,
+    /// Settles or rejects redemptions once the fiat payout is known; the cap limits settlements
+    Operator,
+    /// Pauses operations in an emergency; the cap is not used
+    Guardian,
+    /// Posts proof-of-reserves attestations; the cap is not used
+    Auditor,
+}
+
+impl Role {
+    /// Whether the role's grant limits an amount, so it needs a cap and a period
+    pub fn is_capped(self) -> bool {
+        !matches!(self, Role::Guardian | Role::Auditor)
+    }
+}
+
+/// Fiat reserves backing the token as reported by an auditor, with `reserve_amount`
+/// in the token's smallest unit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub auditor: Address,
+    pub reserve_amount: i128,
+    pub currency: String,
+    pub timestamp: u64,
+    pub report_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    Mint,
+    Burn,
+    Transfer,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PausedOps {
+    pub mint: bool,
+    pub burn: bool,
+    pub transfer: bool,
+}
+
+impl PausedOps {
+    pub fn is_paused(&self, op: Operation) -> bool {
+        match op {
+            Operation::Mint => self.mint,
+            Operation::Burn => self.burn,
+            Operation::Transfer => self.transfer,
+        }
+    }
+
+    pub fn set(&mut self, op: Operation, paused: bool) {
+        match op {
+            Operation::Mint => self.mint = paused,
+            Operation::Burn => self.burn = paused,
+            Operation::Transfer => self.transfer = paused,
+        }
+    }
+}
+
+/// A role held by an address, limited to `cap` tokens every `period_ledgers` ledgers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGrant {
+    pub cap: i128,
+    pub period_ledgers: u32,
+    pub period_start: u32,
+    pub used: i128,
+}
+
+/// A mint backed by a fiat deposit, keyed by the hash of the payment reference
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositRecord {
+    pub minter: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RedemptionStatus {
+    Pending,
+    Settled,
+    Rejected,
+}
+
+/// Tokens held by the contract until the fiat payout for `payout_ref_hash` is made
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Redemption {
+    pub id: u64,
+    pub from: Address,
+    pub amount: i128,
+    pub payout_ref_hash: BytesN<32>,
+    pub status: RedemptionStatus,
+    pub requested_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Allowance(AllowanceDataKey),
+    Balance(Address),
+    Role(Role, Address),
+    Frozen(Address),
+    Deposit(BytesN<32>),
+    Redemption(u64),
+}
+
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&ADMIN_KEY)
+}
 
 pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&ADMIN_KEY, admin);
@@ -11,19 +175,286 @@ pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&ADMIN_KEY).unwrap()
 }
 
-fn balances(env: &Env) -> Map<Address, i128> {
+pub fn set_metadata(env: &Env, metadata: &TokenMetadata) {
+    env.storage().instance().set(&METADATA_KEY, metadata);
+}
+
+pub fn get_metadata(env: &Env) -> TokenMetadata {
+    env.storage().instance().get(&METADATA_KEY).unwrap()
+}
+
+pub fn get_total_supply(env: &Env) -> i128 {
+    env.storage().instance().get(&TOTAL_SUPPLY_KEY).unwrap_or(0)
+}
+
+pub fn set_total_supply(env: &Env, total_supply: i128) {
+    env.storage()
+        .instance()
+        .set(&TOTAL_SUPPLY_KEY, &total_supply);
+}
+
+pub fn get_supply_ceiling(env: &Env) -> Option<i128> {
+    env.storage().instance().get(&SUPPLY_CEILING_KEY)
+}
+
+pub fn set_supply_ceiling(env: &Env, ceiling: i128) {
+    env.storage().instance().set(&SUPPLY_CEILING_KEY, &ceiling);
+}
+
+pub fn get_paused_ops(env: &Env) -> PausedOps {
+    env.storage()
+        .instance()
+        .get(&PAUSED_OPS_KEY)
+        .unwrap_or_default()
+}
+
+pub fn set_paused_ops(env: &Env, paused: &PausedOps) {
+    env.storage().instance().set(&PAUSED_OPS_KEY, paused);
+}
+
+pub fn get_attestation(env: &Env) -> Option<Attestation> {
+    env.storage().instance().get(&ATTESTATION_KEY)
+}
+
+pub fn set_attestation(env: &Env, attestation: &Attestation) {
+    env.storage().instance().set(&ATTESTATION_KEY, attestation);
+}
+
+pub fn get_reserve_check(env: &Env) -> bool {
     env.storage()
         .instance()
-        .get(&BALANCES_KEY)
-        .unwrap_or(Map::new(env))
+        .get(&RESERVE_CHECK_KEY)
+        .unwrap_or(false)
+}
+
+pub fn set_reserve_check(env: &Env, enabled: bool) {
+    env.storage().instance().set(&RESERVE_CHECK_KEY, &enabled);
+}
+
+pub fn get_role(env: &Env, role: Role, account: &Address) -> Option<RoleGrant> {
+    let key = DataKey::Role(role, account.clone());
+    let grant = env.storage().persistent().get(&key);
+    if grant.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            BALANCE_LIFETIME_THRESHOLD,
+            BALANCE_BUMP_AMOUNT,
+        );
+    }
+    grant
+}
+
+pub fn set_role(env: &Env, role: Role, account: &Address, grant: &RoleGrant) {
+    let key = DataKey::Role(role, account.clone());
+    env.storage().persistent().set(&key, grant);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_role(env: &Env, role: Role, account: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Role(role, account.clone()));
+}
+
+pub fn is_frozen(env: &Env, account: &Address) -> bool {
+    let key = DataKey::Frozen(account.clone());
+    let frozen = env.storage().persistent().has(&key);
+    if frozen {
+        env.storage().persistent().extend_ttl(
+            &key,
+            BALANCE_LIFETIME_THRESHOLD,
+            BALANCE_BUMP_AMOUNT,
+        );
+    }
+    frozen
+}
+
+pub fn set_frozen(env: &Env, account: &Address, frozen: bool) {
+    let key = DataKey::Frozen(account.clone());
+    if frozen {
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(
+            &key,
+            BALANCE_LIFETIME_THRESHOLD,
+            BALANCE_BUMP_AMOUNT,
+        );
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+// Records are never removed; an archived record has to be restored before it can be
+// read, so an expired TTL cannot let a reference be minted twice
+pub fn get_deposit(env: &Env, reference: &BytesN<32>) -> Option<DepositRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Deposit(reference.clone()))
+}
+
+pub fn set_deposit(env: &Env, reference: &BytesN<32>, record: &DepositRecord) {
+    let key = DataKey::Deposit(reference.clone());
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn next_redemption_id(env: &Env) -> u64 {
+    let id = env
+        .storage()
+        .instance()
+        .get(&REDEMPTION_COUNT_KEY)
+        .unwrap_or(0u64)
+        + 1;
+    env.storage().instance().set(&REDEMPTION_COUNT_KEY, &id);
+    id
+}
+
+pub fn get_redemption(env: &Env, id: u64) -> Option<Redemption> {
+    env.storage().persistent().get(&DataKey::Redemption(id))
+}
+
+pub fn set_redemption(env: &Env, redemption: &Redemption) {
+    let key = DataKey::Redemption(redemption.id);
+    env.storage().persistent().set(&key, redemption);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn legacy_balances(env: &Env) -> Option<Map<Address, i128>> {
+    env.storage().instance().get(&LEGACY_BALANCES_KEY)
 }
 
 pub fn get_balance(env: &Env, user: &Address) -> i128 {
-    balances(env).get(user.clone()).unwrap_or(0)
+    let key = DataKey::Balance(user.clone());
+    if let Some(balance) = env.storage().persistent().get::<_, i128>(&key) {
+        env.storage().persistent().extend_ttl(
+            &key,
+            BALANCE_LIFETIME_THRESHOLD,
+            BALANCE_BUMP_AMOUNT,
+        );
+        return balance;
+    }
+
+    // Holders not yet moved out of the legacy map
+    legacy_balances(env)
+        .and_then(|map| map.get(user.clone()))
+        .unwrap_or(0)
 }
 
 pub fn set_balance(env: &Env, user: &Address, amount: i128) {
-    let mut map = balances(env);
-    map.set(user.clone(), amount);
-    env.storage().instance().set(&BALANCES_KEY, &map);
+    let key = DataKey::Balance(user.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Move up to `limit` holders from the legacy map into per-holder entries,
+/// returning how many are left to move.
+///
+/// The map is left untouched until the last batch, since rewriting it is as costly as
+/// the map is large. Holders that have traded since the upgrade already have an entry,
+/// which takes precedence over the legacy balance.
+pub fn migrate_legacy_balances(env: &Env, limit: u32) -> u32 {
+    let Some(map) = legacy_balances(env) else {
+        return 0;
+    };
+
+    let migrated: u32 = env
+        .storage()
+        .instance()
+        .get(&MIGRATED_HOLDERS_KEY)
+        .unwrap_or(0);
+    let end = map.len().min(migrated.saturating_add(limit));
+    let holders = map.keys();
+    let balances = map.values();
+
+    // Supply tracking started at the upgrade, so it only holds mints and burns since then
+    if !env.storage().instance().has(&LEGACY_SUPPLY_COUNTED_KEY) {
+        let legacy_supply: i128 = balances.iter().sum();
+        set_total_supply(env, get_total_supply(env) + legacy_supply);
+        env.storage()
+            .instance()
+            .set(&LEGACY_SUPPLY_COUNTED_KEY, &true);
+    }
+
+    for index in migrated..end {
+        let key = DataKey::Balance(holders.get_unchecked(index));
+        if !env.storage().persistent().has(&key) {
+            env.storage()
+                .persistent()
+                .set(&key, &balances.get_unchecked(index));
+        }
+        env.storage().persistent().extend_ttl(
+            &key,
+            BALANCE_LIFETIME_THRESHOLD,
+            BALANCE_BUMP_AMOUNT,
+        );
+    }
+
+    if end == map.len() {
+        env.storage().instance().remove(&LEGACY_BALANCES_KEY);
+        env.storage().instance().remove(&MIGRATED_HOLDERS_KEY);
+        env.storage().instance().remove(&LEGACY_SUPPLY_COUNTED_KEY);
+    } else {
+        env.storage().instance().set(&MIGRATED_HOLDERS_KEY, &end);
+    }
+    map.len() - end
+}
+
+/// Allowances live in temporary storage and read as zero once expired
+pub fn get_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        Some(allowance) => AllowanceValue {
+            amount: 0,
+            expiration_ledger: allowance.expiration_ledger,
+        },
+        None => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+pub fn set_allowance(
+    env: &Env,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    env.storage().temporary().set(
+        &key,
+        &AllowanceValue {
+            amount,
+            expiration_ledger,
+        },
+    );
+
+    // Keep the entry around exactly until it expires
+    if amount > 0 {
+        let live_for = expiration_ledger - env.ledger().sequence();
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, live_for, live_for);
+    }
 }
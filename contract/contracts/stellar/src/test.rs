@@ -1,12 +1,33 @@
 #![cfg(test)]
+extern crate std;
 
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    Address, Env,
+    symbol_short,
+    testutils::{
+        Address as _, AuthorizedFunction, AuthorizedInvocation, EnvTestConfig, Events, Ledger,
+    },
+    vec, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Vec,
 };
 
+use crate::error::ContractError;
 use crate::AfrIContract;
 use crate::AfrIContractClient;
+use crate::{
+    Attestation, DepositRecord, Operation, PausedOps, Redemption, RedemptionStatus, Role, RoleGrant,
+};
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+// Initializes the token and makes the admin a minter, as a deployment script would
+fn init_token(env: &Env, client: &AfrIContractClient, admin: &Address) {
+    client.init(
+        admin,
+        &7,
+        &String::from_str(env, "AFRI Stablecoin"),
+        &String::from_str(env, "AFRI"),
+    );
+    client.grant_role(&Role::Minter, admin, &1_000_000, &DAY_IN_LEDGERS);
+}
 
 #[test]
 fn mint_transfer_burn_flow_works() {
@@ -23,7 +44,7 @@ fn mint_transfer_burn_flow_works() {
     let client = AfrIContractClient::new(&env, &contract_id);
 
     // Initialize contract
-    client.init(&admin);
+    init_token(&env, &client, &admin);
 
     // Mint by admin
     client.mint(&admin, &user1, &100);
@@ -52,7 +73,7 @@ fn transfer_fails_on_insufficient_balance() {
     let contract_id = env.register(AfrIContract, ());
     let client = AfrIContractClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    init_token(&env, &client, &admin);
 
     // No mint → should fail
     client.transfer(&user1, &user2, &10);
@@ -60,7 +81,7 @@ fn transfer_fails_on_insufficient_balance() {
 
 #[test]
 #[should_panic]
-fn mint_fails_for_non_admin() {
+fn mint_fails_for_non_minter() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -70,8 +91,994 @@ fn mint_fails_for_non_admin() {
     let contract_id = env.register(AfrIContract, ());
     let client = AfrIContractClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    init_token(&env, &client, &admin);
 
-    // Attacker tries to mint → should panic
+    // Attacker holds no minter role → should panic
     client.mint(&attacker, &attacker, &100);
 }
+
+#[test]
+fn metadata_is_set_on_init() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.name(), String::from_str(&env, "AFRI Stablecoin"));
+    assert_eq!(client.symbol(), String::from_str(&env, "AFRI"));
+}
+
+#[test]
+#[should_panic]
+fn init_fails_when_already_initialized() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    init_token(&env, &client, &Address::generate(&env));
+}
+
+#[test]
+fn approve_and_transfer_from_work() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &owner, &100);
+
+    let expiration_ledger = env.ledger().sequence() + 100;
+    client.approve(&owner, &spender, &50, &expiration_ledger);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("approve"), owner.clone(), spender.clone()).into_val(&env),
+                (50_i128, expiration_ledger).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.allowance(&owner, &spender), 50);
+
+    client.transfer_from(&spender, &owner, &recipient, &30);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("transfer"), owner.clone(), recipient.clone()).into_val(&env),
+                30_i128.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.allowance(&owner, &spender), 20);
+    assert_eq!(client.balance(&owner), 70);
+    assert_eq!(client.balance(&recipient), 30);
+
+    client.burn_from(&spender, &owner, &20);
+    assert_eq!(client.allowance(&owner, &spender), 0);
+    assert_eq!(client.balance(&owner), 50);
+}
+
+#[test]
+#[should_panic]
+fn transfer_from_fails_above_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &owner, &100);
+    client.approve(&owner, &spender, &10, &(env.ledger().sequence() + 100));
+
+    client.transfer_from(&spender, &owner, &spender, &11);
+}
+
+#[test]
+fn allowance_expires_at_expiration_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &owner, &100);
+
+    let expiration_ledger = env.ledger().sequence() + 10;
+    client.approve(&owner, &spender, &50, &expiration_ledger);
+
+    env.ledger().set_sequence_number(expiration_ledger);
+    assert_eq!(client.allowance(&owner, &spender), 50);
+
+    env.ledger().set_sequence_number(expiration_ledger + 1);
+    assert_eq!(client.allowance(&owner, &spender), 0);
+    assert!(client
+        .try_transfer_from(&spender, &owner, &spender, &1)
+        .is_err());
+}
+
+#[test]
+#[should_panic]
+fn approve_fails_with_past_expiration_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    env.ledger().set_sequence_number(100);
+
+    client.approve(&owner, &spender, &50, &99);
+}
+
+#[test]
+fn transfer_and_burn_emit_standard_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    client.mint(&admin, &user1, &100);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("mint"), user1.clone()).into_val(&env),
+                100_i128.into_val(&env),
+            ),
+        ]
+    );
+
+    client.transfer(&user1, &user2, &40);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("transfer"), user1.clone(), user2.clone()).into_val(&env),
+                40_i128.into_val(&env),
+            ),
+        ]
+    );
+
+    client.burn(&user2, &15);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("burn"), user2.clone()).into_val(&env),
+                15_i128.into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn balances_scale_to_thousands_of_holders() {
+    // Thousands of ledger entries would make for an unwieldy snapshot
+    let env = Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    });
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    let mut holders = Vec::new(&env);
+    for _ in 0..2_000 {
+        let holder = Address::generate(&env);
+        client.mint(&admin, &holder, &100);
+        holders.push_back(holder);
+    }
+
+    let first = holders.first_unchecked();
+    let last = holders.last_unchecked();
+    client.transfer(&first, &last, &40);
+
+    // Only the two holders' entries and the contract instance are written,
+    // however many holders exist
+    assert_eq!(env.cost_estimate().resources().write_entries, 3);
+    assert_eq!(client.balance(&first), 60);
+    assert_eq!(client.balance(&last), 140);
+    assert_eq!(client.balance(&holders.get_unchecked(1_000)), 100);
+}
+
+#[test]
+fn legacy_balance_map_is_migrated() {
+    let env = Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    });
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    // Seed the balance map the way the contract stored it before per-holder
+    // entries, close to the instance entry size limit
+    env.cost_estimate().budget().reset_unlimited();
+    let mut legacy = Map::new(&env);
+    for _ in 0..1_000 {
+        legacy.set(Address::generate(&env), 100_i128);
+    }
+    let holders = legacy.keys();
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(&symbol_short!("BAL"), &legacy);
+    });
+    env.cost_estimate().budget().reset_default();
+
+    // Holders can trade before they have been migrated
+    let sender = holders.get_unchecked(999);
+    let recipient = Address::generate(&env);
+    client.transfer(&sender, &recipient, &30);
+    assert_eq!(client.balance(&sender), 70);
+    assert_eq!(client.balance(&recipient), 30);
+    assert_eq!(client.balance(&holders.get_unchecked(0)), 100);
+
+    // A batch has to fit in a single transaction's budget
+    let mut remaining = 1_000;
+    while remaining > 0 {
+        remaining -= 100;
+        assert_eq!(client.migrate_balances(&100), remaining);
+    }
+    assert_eq!(client.migrate_balances(&100), 0);
+    assert_eq!(client.total_supply(), 100_000);
+
+    let legacy_removed = env.as_contract(&contract_id, || {
+        !env.storage().instance().has(&symbol_short!("BAL"))
+    });
+    assert!(legacy_removed);
+    assert_eq!(client.balance(&sender), 70);
+    for holder in holders.iter().take(999) {
+        assert_eq!(client.balance(&holder), 100);
+    }
+}
+
+#[test]
+fn empty_migration_batches_do_not_recount_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    let mut legacy = Map::new(&env);
+    for _ in 0..3 {
+        legacy.set(Address::generate(&env), 100_i128);
+    }
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(&symbol_short!("BAL"), &legacy);
+    });
+
+    assert_eq!(client.migrate_balances(&0), 3);
+    assert_eq!(client.migrate_balances(&0), 3);
+    assert_eq!(client.migrate_balances(&10), 0);
+    assert_eq!(client.total_supply(), 300);
+}
+
+#[test]
+fn total_supply_tracks_mints_and_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    assert_eq!(client.total_supply(), 0);
+
+    client.mint(&admin, &user1, &100);
+    client.mint(&admin, &user2, &50);
+    assert_eq!(client.total_supply(), 150);
+
+    client.transfer(&user1, &user2, &40);
+    assert_eq!(client.total_supply(), 150);
+
+    client.burn(&user2, &30);
+    assert_eq!(client.total_supply(), 120);
+}
+
+#[test]
+fn mint_requires_minter_auth() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Minter, &minter, &1_000, &DAY_IN_LEDGERS);
+    env.set_auths(&[]);
+
+    // Naming a minter is not enough without its signature
+    assert!(client.try_mint(&minter, &user, &100).is_err());
+
+    env.mock_all_auths();
+    client.mint(&minter, &user, &100);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            minter.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "mint"),
+                    (minter.clone(), user.clone(), 100_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn minter_cap_resets_each_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Minter, &minter, &100, &DAY_IN_LEDGERS);
+
+    client.mint(&minter, &user, &60);
+    assert!(client.try_mint(&minter, &user, &50).is_err());
+    client.mint(&minter, &user, &40);
+    assert_eq!(
+        client.role(&Role::Minter, &minter),
+        Some(RoleGrant {
+            cap: 100,
+            period_ledgers: DAY_IN_LEDGERS,
+            period_start: 0,
+            used: 100,
+        })
+    );
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = DAY_IN_LEDGERS);
+    client.mint(&minter, &user, &100);
+    assert_eq!(client.balance(&user), 200);
+}
+
+#[test]
+fn revoked_minter_cannot_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Minter, &minter, &100, &DAY_IN_LEDGERS);
+    client.mint(&minter, &user, &10);
+
+    client.revoke_role(&Role::Minter, &minter);
+    assert_eq!(client.role(&Role::Minter, &minter), None);
+    assert!(client.try_mint(&minter, &user, &10).is_err());
+}
+
+#[test]
+fn supply_ceiling_caps_mints() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &user, &100);
+
+    // The ceiling cannot be set below what is already in circulation
+    assert!(client.try_set_supply_ceiling(&99).is_err());
+
+    client.set_supply_ceiling(&150);
+    assert_eq!(client.supply_ceiling(), Some(150));
+    assert!(client.try_mint(&admin, &user, &51).is_err());
+    client.mint(&admin, &user, &50);
+    assert_eq!(client.total_supply(), 150);
+}
+
+#[test]
+fn burner_burns_within_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let burner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &user, &100);
+
+    // Minting rights do not carry over to burning
+    assert!(client.try_burn_by(&admin, &user, &10).is_err());
+
+    client.grant_role(&Role::Burner, &burner, &50, &DAY_IN_LEDGERS);
+    // The role alone does not let a burner take a holder's funds
+    assert_eq!(
+        client.try_burn_by(&burner, &user, &30),
+        Err(Ok(ContractError::InsufficientAllowance.into()))
+    );
+
+    client.approve(&user, &burner, &100, &1_000);
+    client.burn_by(&burner, &user, &30);
+    assert_eq!(client.allowance(&user, &burner), 70);
+    assert_eq!(
+        client.try_burn_by(&burner, &user, &30),
+        Err(Ok(ContractError::RoleCapExceeded.into()))
+    );
+    assert_eq!(client.balance(&user), 70);
+    assert_eq!(client.total_supply(), 70);
+}
+
+#[test]
+fn uncapped_roles_ignore_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let burner = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    client.grant_role(&Role::Guardian, &guardian, &0, &0);
+    assert_eq!(
+        client.role(&Role::Guardian, &guardian),
+        Some(RoleGrant {
+            cap: 0,
+            period_ledgers: 0,
+            period_start: env.ledger().sequence(),
+            used: 0,
+        })
+    );
+    client.pause(&guardian, &Operation::Transfer);
+    assert!(client.paused_ops().transfer);
+
+    assert_eq!(
+        client.try_grant_role(&Role::Burner, &burner, &0, &0),
+        Err(Ok(ContractError::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn frozen_account_cannot_move_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &user1, &100);
+    client.mint(&admin, &user2, &100);
+
+    client.freeze(&admin, &user1);
+    assert!(client.is_frozen(&user1));
+
+    let frozen = Err(Ok(ContractError::AccountFrozen.into()));
+    assert_eq!(client.try_transfer(&user1, &user2, &10), frozen);
+    assert_eq!(client.try_transfer(&user2, &user1, &10), frozen);
+    assert_eq!(client.try_burn(&user1, &10), frozen);
+    assert_eq!(client.try_mint(&admin, &user1, &10), frozen);
+
+    client.approve(&user1, &user2, &50, &1_000);
+    assert_eq!(
+        client.try_transfer_from(&user2, &user1, &user2, &10),
+        frozen
+    );
+
+    client.unfreeze(&admin, &user1);
+    assert!(!client.is_frozen(&user1));
+    client.transfer(&user1, &user2, &10);
+    assert_eq!(client.balance(&user1), 90);
+}
+
+#[test]
+fn compliance_role_can_freeze_and_claw_back() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let officer = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &user, &100);
+
+    // Holding no role, the officer cannot act yet
+    assert!(client.try_freeze(&officer, &user).is_err());
+
+    client.grant_role(&Role::Compliance, &officer, &50, &DAY_IN_LEDGERS);
+    client.freeze(&officer, &user);
+    client.clawback(&officer, &user, &40);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("clawback"), user.clone()).into_val(&env),
+                40_i128.into_val(&env),
+            ),
+        ]
+    );
+
+    // Beyond its cap only the admin can claw back
+    assert_eq!(
+        client.try_clawback(&officer, &user, &20),
+        Err(Ok(ContractError::RoleCapExceeded.into()))
+    );
+    client.clawback(&admin, &user, &20);
+
+    assert_eq!(client.balance(&user), 40);
+    assert_eq!(client.total_supply(), 40);
+}
+
+#[test]
+fn deposit_reference_mints_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+
+    let reference = env
+        .crypto()
+        .sha256(&Bytes::from_slice(&env, b"PSK_ref_8f2a1c"))
+        .to_bytes();
+    client.mint_for_deposit(&admin, &user, &500, &reference);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("mint"), user.clone()).into_val(&env),
+                500_i128.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("deposit"), reference.clone()).into_val(&env),
+                (user.clone(), 500_i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(
+        client.deposit(&reference),
+        Some(DepositRecord {
+            minter: admin.clone(),
+            to: user.clone(),
+            amount: 500,
+            ledger: 0,
+        })
+    );
+
+    // A retried callback for the same payment is rejected
+    assert_eq!(
+        client.try_mint_for_deposit(&admin, &user, &500, &reference),
+        Err(Ok(ContractError::DuplicateDeposit.into()))
+    );
+    assert_eq!(client.balance(&user), 500);
+    assert_eq!(client.total_supply(), 500);
+}
+
+#[test]
+fn settled_redemption_burns_escrowed_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Operator, &operator, &1_000, &DAY_IN_LEDGERS);
+    client.mint(&admin, &user, &100);
+
+    let payout_ref_hash = BytesN::from_array(&env, &[7; 32]);
+    let id = client.request_redemption(&user, &60, &payout_ref_hash);
+    let mut redemption = Redemption {
+        id,
+        from: user.clone(),
+        amount: 60,
+        payout_ref_hash,
+        status: RedemptionStatus::Pending,
+        requested_at: 0,
+    };
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("transfer"), user.clone(), contract_id.clone()).into_val(&env),
+                60_i128.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (
+                    symbol_short!("redeem"),
+                    symbol_short!("requested"),
+                    user.clone()
+                )
+                    .into_val(&env),
+                redemption.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.balance(&user), 40);
+    assert_eq!(client.balance(&contract_id), 60);
+    assert_eq!(client.total_supply(), 100);
+
+    // Only an operator can settle
+    assert!(client.try_settle_redemption(&user, &id).is_err());
+
+    client.settle_redemption(&operator, &id);
+    redemption.status = RedemptionStatus::Settled;
+    assert_eq!(client.redemption(&id), Some(redemption));
+    assert_eq!(client.balance(&contract_id), 0);
+    assert_eq!(client.total_supply(), 40);
+
+    assert_eq!(
+        client.try_reject_redemption(&operator, &id),
+        Err(Ok(ContractError::RedemptionNotPending.into()))
+    );
+}
+
+#[test]
+fn rejected_redemption_refunds_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Operator, &operator, &1_000, &DAY_IN_LEDGERS);
+    client.mint(&admin, &user, &100);
+
+    let id = client.request_redemption(&user, &60, &BytesN::from_array(&env, &[7; 32]));
+    client.reject_redemption(&operator, &id);
+
+    assert_eq!(
+        client.redemption(&id).unwrap().status,
+        RedemptionStatus::Rejected
+    );
+    assert_eq!(client.balance(&user), 100);
+    assert_eq!(client.balance(&contract_id), 0);
+    assert_eq!(client.total_supply(), 100);
+    assert_eq!(
+        client.try_settle_redemption(&operator, &id),
+        Err(Ok(ContractError::RedemptionNotPending.into()))
+    );
+    assert_eq!(
+        client.try_settle_redemption(&operator, &(id + 1)),
+        Err(Ok(ContractError::RedemptionNotFound.into()))
+    );
+}
+
+fn setup_paused_token(env: &Env) -> (AfrIContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+
+    let admin = Address::generate(env);
+    let guardian = Address::generate(env);
+    let user = Address::generate(env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(env, &contract_id);
+
+    init_token(env, &client, &admin);
+    client.grant_role(&Role::Guardian, &guardian, &0, &0);
+    client.grant_role(&Role::Burner, &admin, &1_000, &DAY_IN_LEDGERS);
+    client.mint(&admin, &user, &100);
+
+    (client, admin, guardian, user)
+}
+
+#[test]
+fn paused_mint_blocks_only_minting() {
+    let env = Env::default();
+    let (client, admin, guardian, user) = setup_paused_token(&env);
+
+    client.pause(&guardian, &Operation::Mint);
+    assert_eq!(
+        client.paused_ops(),
+        PausedOps {
+            mint: true,
+            burn: false,
+            transfer: false,
+        }
+    );
+
+    let paused = Err(Ok(ContractError::OperationPaused.into()));
+    assert_eq!(client.try_mint(&admin, &user, &10), paused);
+    assert_eq!(
+        client.try_mint_for_deposit(&admin, &user, &10, &BytesN::from_array(&env, &[1; 32])),
+        paused
+    );
+    client.transfer(&user, &admin, &10);
+    client.burn(&user, &10);
+
+    client.unpause(&Operation::Mint);
+    client.mint(&admin, &user, &10);
+    assert_eq!(client.balance(&user), 90);
+}
+
+#[test]
+fn paused_burn_blocks_only_burning() {
+    let env = Env::default();
+    let (client, admin, guardian, user) = setup_paused_token(&env);
+
+    client.pause(&guardian, &Operation::Burn);
+    assert!(client.paused_ops().burn);
+
+    let paused = Err(Ok(ContractError::OperationPaused.into()));
+    assert_eq!(client.try_burn(&user, &10), paused);
+    client.approve(&user, &admin, &50, &1_000);
+    assert_eq!(client.try_burn_by(&admin, &user, &10), paused);
+    assert_eq!(client.try_burn_from(&admin, &user, &10), paused);
+    client.mint(&admin, &user, &10);
+    client.transfer(&user, &admin, &10);
+
+    client.unpause(&Operation::Burn);
+    client.burn(&user, &10);
+    assert_eq!(client.balance(&user), 90);
+}
+
+#[test]
+fn paused_transfer_blocks_only_transfers() {
+    let env = Env::default();
+    let (client, admin, guardian, user) = setup_paused_token(&env);
+
+    client.pause(&guardian, &Operation::Transfer);
+    assert!(client.paused_ops().transfer);
+
+    let paused = Err(Ok(ContractError::OperationPaused.into()));
+    assert_eq!(client.try_transfer(&user, &admin, &10), paused);
+    client.approve(&user, &admin, &50, &1_000);
+    assert_eq!(client.try_transfer_from(&admin, &user, &admin, &10), paused);
+    assert_eq!(
+        client.try_request_redemption(&user, &10, &BytesN::from_array(&env, &[1; 32])),
+        Err(Ok(ContractError::OperationPaused.into()))
+    );
+    client.mint(&admin, &user, &10);
+    client.burn(&user, &10);
+
+    client.unpause(&Operation::Transfer);
+    client.transfer(&user, &admin, &10);
+    assert_eq!(client.balance(&user), 90);
+}
+
+#[test]
+fn only_admin_can_unpause() {
+    let env = Env::default();
+    let (client, admin, guardian, user) = setup_paused_token(&env);
+
+    // Without a guardian role nobody but the admin can pause
+    assert_eq!(
+        client.try_pause(&user, &Operation::Transfer),
+        Err(Ok(ContractError::Unauthorized.into()))
+    );
+    client.pause(&admin, &Operation::Transfer);
+    client.pause(&guardian, &Operation::Mint);
+
+    env.set_auths(&[]);
+    assert!(client.try_unpause(&Operation::Transfer).is_err());
+
+    env.mock_all_auths();
+    client.unpause(&Operation::Transfer);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    client.address.clone(),
+                    symbol_short!("unpause"),
+                    (Operation::Transfer,).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(
+        client.paused_ops(),
+        PausedOps {
+            mint: true,
+            burn: false,
+            transfer: false,
+        }
+    );
+}
+
+#[test]
+fn auditor_attests_reserves() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.mint(&admin, &user, &800);
+
+    let ngn = String::from_str(&env, "NGN");
+    let report_hash = BytesN::from_array(&env, &[9; 32]);
+    assert_eq!(
+        client.try_post_attestation(&admin, &1_000, &ngn, &900, &report_hash),
+        Err(Ok(ContractError::Unauthorized.into()))
+    );
+
+    client.grant_role(&Role::Auditor, &auditor, &1, &DAY_IN_LEDGERS);
+    assert_eq!(client.collateralization_ratio(), None);
+    client.post_attestation(&auditor, &1_000, &ngn, &900, &report_hash);
+
+    assert_eq!(
+        client.latest_attestation(),
+        Some(Attestation {
+            auditor: auditor.clone(),
+            reserve_amount: 1_000,
+            currency: ngn.clone(),
+            timestamp: 900,
+            report_hash: report_hash.clone(),
+        })
+    );
+    assert_eq!(client.collateralization_ratio(), Some(12_500));
+
+    // Stale or future-dated reports are refused
+    let invalid = Err(Ok(ContractError::InvalidAttestation.into()));
+    assert_eq!(
+        client.try_post_attestation(&auditor, &1_000, &ngn, &900, &report_hash),
+        invalid
+    );
+    assert_eq!(
+        client.try_post_attestation(&auditor, &1_000, &ngn, &1_001, &report_hash),
+        invalid
+    );
+}
+
+#[test]
+fn reserve_check_refuses_unbacked_mints() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let contract_id = env.register(AfrIContract, ());
+    let client = AfrIContractClient::new(&env, &contract_id);
+
+    init_token(&env, &client, &admin);
+    client.grant_role(&Role::Auditor, &auditor, &1, &DAY_IN_LEDGERS);
+    client.set_reserve_check(&true);
+
+    // Nothing can be minted before the first attestation
+    let unbacked = Err(Ok(ContractError::ReservesExceeded.into()));
+    assert_eq!(client.try_mint(&admin, &user, &1), unbacked);
+
+    client.post_attestation(
+        &auditor,
+        &500,
+        &String::from_str(&env, "NGN"),
+        &1_000,
+        &BytesN::from_array(&env, &[9; 32]),
+    );
+    client.mint(&admin, &user, &500);
+    assert_eq!(client.try_mint(&admin, &user, &1), unbacked);
+
+    client.set_reserve_check(&false);
+    client.mint(&admin, &user, &1);
+    assert_eq!(client.collateralization_ratio(), Some(9_980));
+}
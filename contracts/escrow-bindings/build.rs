@@ -0,0 +1,5 @@
+// The bindings are generated from the contract WASM when this crate compiles, so
+// cargo has to rebuild them whenever the contract is rebuilt.
+fn main() {
+    println!("cargo:rerun-if-changed=../../target/wasm32v1-none/contract/escrow.wasm");
+}
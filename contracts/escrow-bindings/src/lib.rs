@@ -0,0 +1,14 @@
+//! Client and types for `EscrowContract`, generated from the contract's WASM spec.
+//!
+//! The WASM has to be built before this crate:
+//!
+//! ```text
+//! cargo build -p escrow --target wasm32v1-none --profile contract
+//! ```
+#![no_std]
+// The generated client mirrors the contract's order constructors
+#![allow(clippy::too_many_arguments)]
+
+soroban_sdk::contractimport!(file = "../../target/wasm32v1-none/contract/escrow.wasm");
+
+mod test;
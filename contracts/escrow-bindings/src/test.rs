@@ -0,0 +1,26 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, Env};
+
+#[test]
+fn test_client_calls_deployed_wasm() {
+    let env = Env::default();
+    // The budget is shared by every call in the test, and each one instantiates the WASM
+    env.budget().reset_unlimited();
+    let contract_id = env.register_contract_wasm(None, WASM);
+    let client = Client::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let resolver = Address::generate(&env);
+    client.initialize(&admin, &50, &treasury, &resolver);
+
+    assert_eq!(client.get_admin(), admin);
+    assert!(!client.is_paused());
+    assert_eq!(
+        client.try_get_order(&1),
+        Err(Ok(soroban_sdk::Error::from(Error::OrderNotFound)))
+    );
+}
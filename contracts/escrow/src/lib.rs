@@ -0,0 +1,2244 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, token, Address, BytesN, Env, Map, String,
+    Symbol, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    Unauthorized = 3,
+    InvalidFeeRate = 4,
+    ContractPaused = 5,
+    NoPendingAdmin = 6,
+    ChangeNotFound = 7,
+    TimelockNotElapsed = 8,
+    AlreadyMigrated = 9,
+    MigrationPending = 10,
+    InvalidTimelockDelay = 11,
+    UnsupportedVersion = 12,
+    OrderNotFound = 100,
+    InvalidOrderStatus = 101,
+    OrderExpired = 102,
+    CannotAcceptOwnOrder = 103,
+    TransferFailed = 104,
+    InvalidAmount = 105,
+    InvalidExpiration = 106,
+    InvalidDisputeShare = 107,
+    DisputeNotFound = 108,
+    InvalidPaymentWindow = 109,
+    PaymentWindowActive = 110,
+    InvalidOrderSide = 111,
+    InvalidFeeTier = 112,
+    TokenNotAllowed = 113,
+    AmountOutOfRange = 114,
+    InvalidTtlConfig = 115,
+    ReputationTooLow = 116,
+    NotArbiter = 117,
+    InvalidArbiterPanel = 119,
+    ArbiterPanelActive = 120,
+    EvidenceLimitReached = 121,
+    TooManyOpenOrders = 122,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrderStatus {
+    Open,
+    Locked,
+    PaymentSent,
+    Completed,
+    Disputed,
+    Cancelled,
+    Resolved,
+}
+
+impl OrderStatus {
+    /// Whether an order in this status can no longer change
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Completed | OrderStatus::Cancelled | OrderStatus::Resolved
+        )
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrderSide {
+    Sell,
+    Buy,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    pub id: u64,
+    pub side: OrderSide,
+    pub seller: Option<Address>,
+    pub buyer: Option<Address>,
+    pub token: Address,
+    pub amount: i128,
+    pub fiat_currency: Symbol,
+    pub fiat_amount: i128,
+    pub rate: i128,
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub payment_window: u64,
+    pub payment_deadline: u64,
+    pub payment_method: String,
+    pub parent_id: Option<u64>,
+    pub fee_rate: u32,
+    pub min_completed_trades: u32,
+    /// When the order reached a final status, or 0 while it is still live
+    pub closed_at: u64,
+}
+
+/// Order layout stored by versions 2 and 3 of the contract, before closing times
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderV2 {
+    pub id: u64,
+    pub side: OrderSide,
+    pub seller: Option<Address>,
+    pub buyer: Option<Address>,
+    pub token: Address,
+    pub amount: i128,
+    pub fiat_currency: Symbol,
+    pub fiat_amount: i128,
+    pub rate: i128,
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub payment_window: u64,
+    pub payment_deadline: u64,
+    pub payment_method: String,
+    pub parent_id: Option<u64>,
+    pub fee_rate: u32,
+    pub min_completed_trades: u32,
+}
+
+/// Order layout stored by version 1 of the contract, before reputation requirements
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct OrderV1 {
+    pub id: u64,
+    pub side: OrderSide,
+    pub seller: Option<Address>,
+    pub buyer: Option<Address>,
+    pub token: Address,
+    pub amount: i128,
+    pub fiat_currency: Symbol,
+    pub fiat_amount: i128,
+    pub rate: i128,
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub payment_window: u64,
+    pub payment_deadline: u64,
+    pub payment_method: String,
+    pub parent_id: Option<u64>,
+    pub fee_rate: u32,
+}
+
+impl From<OrderV1> for OrderV2 {
+    fn from(order: OrderV1) -> Self {
+        OrderV2 {
+            id: order.id,
+            side: order.side,
+            seller: order.seller,
+            buyer: order.buyer,
+            token: order.token,
+            amount: order.amount,
+            fiat_currency: order.fiat_currency,
+            fiat_amount: order.fiat_amount,
+            rate: order.rate,
+            status: order.status,
+            created_at: order.created_at,
+            expires_at: order.expires_at,
+            payment_window: order.payment_window,
+            payment_deadline: order.payment_deadline,
+            payment_method: order.payment_method,
+            parent_id: order.parent_id,
+            fee_rate: order.fee_rate,
+            min_completed_trades: 0,
+        }
+    }
+}
+
+impl From<OrderV2> for Order {
+    fn from(order: OrderV2) -> Self {
+        Order {
+            id: order.id,
+            side: order.side,
+            seller: order.seller,
+            buyer: order.buyer,
+            token: order.token,
+            amount: order.amount,
+            fiat_currency: order.fiat_currency,
+            fiat_amount: order.fiat_amount,
+            rate: order.rate,
+            status: order.status,
+            created_at: order.created_at,
+            expires_at: order.expires_at,
+            payment_window: order.payment_window,
+            payment_deadline: order.payment_deadline,
+            payment_method: order.payment_method,
+            parent_id: order.parent_id,
+            fee_rate: order.fee_rate,
+            min_completed_trades: order.min_completed_trades,
+            closed_at: 0,
+        }
+    }
+}
+
+/// Ends of the open order list for one side of a token and fiat currency pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenOrderList {
+    pub head: Option<u64>,
+    pub tail: Option<u64>,
+}
+
+/// Neighbours of an open order in its list, oldest first
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenOrderLink {
+    pub prev: Option<u64>,
+    pub next: Option<u64>,
+}
+
+/// A page of open orders; pass `next_cursor` back to `get_open_orders` for the next page
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenOrderPage {
+    pub orders: Vec<Order>,
+    pub next_cursor: Option<u64>,
+}
+
+/// Hash of off-chain dispute evidence, such as a sha256 of chat logs or a bank receipt
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Evidence {
+    pub submitted_by: Address,
+    pub hash: BytesN<32>,
+    pub submitted_at: u64,
+}
+
+/// Arbiters deciding disputes by a `threshold`-of-n vote in place of the dispute resolver.
+///
+/// An empty panel with a zero threshold leaves disputes to the dispute resolver.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbiterPanel {
+    pub arbiters: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Trade history of an address, updated as its orders reach a final state.
+///
+/// Average release time is `total_release_time / released_trades`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reputation {
+    pub completed_trades: u32,
+    pub cancelled_orders: u32,
+    pub abandoned_trades: u32,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    pub released_trades: u32,
+    pub total_release_time: u64,
+}
+
+/// Fee rate applied to trades of at least `min_amount` of a token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_amount: i128,
+    pub fee_rate: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeResolution {
+    pub resolver: Address,
+    pub buyer_share_bps: u32,
+    pub buyer_amount: i128,
+    pub seller_amount: i128,
+    pub fee: i128,
+    pub resolved_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisputeOutcome {
+    Pending,
+    Resolved(DisputeResolution),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub order_id: u64,
+    pub raised_by: Address,
+    pub previous_status: OrderStatus,
+    pub raised_at: u64,
+    pub outcome: DisputeOutcome,
+}
+
+/// Order size bounds for a token accepted by the escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenLimits {
+    pub min_amount: i128,
+    pub max_amount: i128,
+}
+
+/// Ledger thresholds used when extending the TTL of persistent order entries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+/// A parameter change that has to wait out the timelock before it applies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamChange {
+    FeeRate(u32),
+    FeeTreasury(Address),
+    FeeTiers(Address, Vec<FeeTier>),
+    TimelockDelay(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ParamChange,
+    pub proposed_at: u32,
+    pub executable_at: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    OrderCount,
+    Order(u64),
+    UserOrders(Address),
+    FeeRate,
+    FeeTreasury,
+    IsPaused,
+    DisputeResolver,
+    Dispute(u64),
+    /// Open order ids of a pair as a single vector, replaced by `OpenOrderList` in version 3
+    OpenOrders(OrderSide, Address, Symbol),
+    ChildOrders(u64),
+    FeeTiers(Address),
+    AllowedToken(Address),
+    TtlConfig,
+    PendingAdmin,
+    TimelockDelay,
+    ChangeCount,
+    PendingChange(u64),
+    Version,
+    MigrationCursor,
+    Reputation(Address),
+    ArbiterPanel,
+    DisputeEvidence(u64),
+    DisputeVotes(u64),
+    OpenOrderList(OrderSide, Address, Symbol),
+    OpenOrderLink(u64),
+    OpenOrderCount(Address),
+}
+
+/// Storage layout version written by this build of the contract
+pub const CONTRACT_VERSION: u32 = 4;
+
+/// Number of orders rewritten per `migrate` call, to stay within the resource budget
+const MIGRATION_BATCH_SIZE: u64 = 50;
+
+/// Upper bound on the number of orders returned by a single listing call
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Upper bound on the open orders a single maker can list at once
+const MAX_OPEN_ORDERS_PER_MAKER: u32 = 20;
+
+/// Ledgers per day at a five second close time
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Default TTL settings: entries touched with less than 30 days left are extended to 60 days
+const DEFAULT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const DEFAULT_TTL_EXTEND_TO: u32 = 60 * DAY_IN_LEDGERS;
+
+const DAY_IN_SECONDS: u64 = 86_400;
+
+/// Upper bound on evidence hashes attached to a single dispute
+const MAX_DISPUTE_EVIDENCE: u32 = 20;
+
+/// Default number of ledgers a fee or treasury change waits before it can be executed
+const DEFAULT_TIMELOCK_DELAY: u32 = DAY_IN_LEDGERS;
+
+/// Longest timelock delay that can be set, so a bad proposal cannot lock out later changes
+const MAX_TIMELOCK_DELAY: u32 = 30 * DAY_IN_LEDGERS;
+
+#[contract]
+pub struct EscrowContract;
+
+#[contractimpl]
+impl EscrowContract {
+    /// Initialize the contract with admin settings
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        fee_rate: u32,
+        fee_treasury: Address,
+        dispute_resolver: Address,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+        if fee_rate > 1000 {
+            // Max 10% (1000 basis points)
+            return Err(Error::InvalidFeeRate);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::FeeRate, &fee_rate);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeTreasury, &fee_treasury);
+        env.storage()
+            .instance()
+            .set(&DataKey::DisputeResolver, &dispute_resolver);
+        env.storage().instance().set(&DataKey::IsPaused, &false);
+        env.storage().instance().set(&DataKey::OrderCount, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &CONTRACT_VERSION);
+        Ok(())
+    }
+
+    /// Replace the contract code, keeping storage and the contract address.
+    ///
+    /// The new code should be followed by `migrate` when it changes the storage layout.
+    /// Until the migration finishes, order entrypoints fail with `MigrationPending`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish(
+            (Symbol::new(&env, "contract_upgraded"),),
+            (new_wasm_hash, Self::version(env.clone())),
+        );
+
+        Ok(())
+    }
+
+    /// Rewrite stored orders from the stored version's layout to the current one.
+    ///
+    /// Orders are processed in batches; call repeatedly until it returns `true`, at which
+    /// point the stored version is bumped to `CONTRACT_VERSION`. Orders stored by contracts
+    /// deployed before versioning have no conversion and fail with `UnsupportedVersion`.
+    pub fn migrate(env: Env) -> Result<bool, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        let from_version = Self::version(env.clone());
+        if from_version >= CONTRACT_VERSION {
+            return Err(Error::AlreadyMigrated);
+        }
+
+        let order_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OrderCount)
+            .unwrap_or(0);
+        let cursor: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MigrationCursor)
+            .unwrap_or(0);
+        let end = order_count.min(cursor + MIGRATION_BATCH_SIZE);
+
+        for order_id in (cursor + 1)..=end {
+            Self::migrate_order(&env, order_id, from_version)?;
+        }
+
+        if end < order_count {
+            env.storage()
+                .instance()
+                .set(&DataKey::MigrationCursor, &end);
+            return Ok(false);
+        }
+
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &CONTRACT_VERSION);
+        Self::extend_instance_ttl(&env);
+
+        env.events().publish(
+            (Symbol::new(&env, "contract_migrated"),),
+            (from_version, CONTRACT_VERSION),
+        );
+
+        Ok(true)
+    }
+
+    /// Get the storage layout version; contracts deployed before versioning report 0
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Version).unwrap_or(0)
+    }
+
+    /// Propose a new admin; the handover completes once the new admin accepts
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        env.events()
+            .publish((Symbol::new(&env, "admin_proposed"),), (admin, new_admin));
+
+        Ok(())
+    }
+
+    /// Accept a pending admin proposal, signed by the proposed admin
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        let new_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        new_admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        env.events()
+            .publish((Symbol::new(&env, "admin_accepted"),), (admin, new_admin));
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_admin_proposal(env: Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        let new_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingAdmin)
+            .ok_or(Error::NoPendingAdmin)?;
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_proposal_cancelled"),),
+            (admin, new_admin),
+        );
+
+        Ok(())
+    }
+
+    /// Get the admin waiting to accept the handover, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Queue a platform fee rate change behind the timelock
+    pub fn propose_fee_rate(env: Env, new_fee_rate: u32) -> Result<u64, Error> {
+        if new_fee_rate > 1000 {
+            return Err(Error::InvalidFeeRate);
+        }
+        Self::queue_change(&env, ParamChange::FeeRate(new_fee_rate))
+    }
+
+    /// Queue a fee treasury change behind the timelock
+    pub fn propose_fee_treasury(env: Env, new_treasury: Address) -> Result<u64, Error> {
+        Self::queue_change(&env, ParamChange::FeeTreasury(new_treasury))
+    }
+
+    /// Queue a replacement of the fee tiers for a token behind the timelock; an empty
+    /// list falls back to the global fee rate.
+    ///
+    /// Tiers must be sorted by strictly increasing `min_amount`, and a trade pays the rate
+    /// of the highest tier whose `min_amount` it reaches.
+    pub fn propose_fee_tiers(env: Env, token: Address, tiers: Vec<FeeTier>) -> Result<u64, Error> {
+        let mut previous_min: Option<i128> = None;
+        for tier in tiers.iter() {
+            if tier.fee_rate > 1000 {
+                return Err(Error::InvalidFeeRate);
+            }
+            if tier.min_amount < 0 || previous_min.is_some_and(|min| tier.min_amount <= min) {
+                return Err(Error::InvalidFeeTier);
+            }
+            previous_min = Some(tier.min_amount);
+        }
+        Self::queue_change(&env, ParamChange::FeeTiers(token, tiers))
+    }
+
+    /// Queue a change to the timelock delay itself, measured in ledgers and at most
+    /// `MAX_TIMELOCK_DELAY`
+    pub fn propose_timelock_delay(env: Env, delay: u32) -> Result<u64, Error> {
+        if delay > MAX_TIMELOCK_DELAY {
+            return Err(Error::InvalidTimelockDelay);
+        }
+        Self::queue_change(&env, ParamChange::TimelockDelay(delay))
+    }
+
+    /// Apply a queued change once its timelock has elapsed
+    pub fn execute_change(env: Env, change_id: u64) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        let pending: PendingChange = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingChange(change_id))
+            .ok_or(Error::ChangeNotFound)?;
+        if env.ledger().sequence() < pending.executable_at {
+            return Err(Error::TimelockNotElapsed);
+        }
+
+        match &pending.change {
+            ParamChange::FeeRate(fee_rate) => {
+                env.storage().instance().set(&DataKey::FeeRate, fee_rate);
+            }
+            ParamChange::FeeTreasury(treasury) => {
+                env.storage()
+                    .instance()
+                    .set(&DataKey::FeeTreasury, treasury);
+            }
+            ParamChange::FeeTiers(token, tiers) => {
+                let key = DataKey::FeeTiers(token.clone());
+                if tiers.is_empty() {
+                    env.storage().instance().remove(&key);
+                } else {
+                    env.storage().instance().set(&key, tiers);
+                }
+            }
+            ParamChange::TimelockDelay(delay) => {
+                env.storage().instance().set(&DataKey::TimelockDelay, delay);
+            }
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingChange(change_id));
+
+        env.events().publish(
+            (Symbol::new(&env, "change_executed"),),
+            (change_id, pending.change),
+        );
+
+        Ok(())
+    }
+
+    /// Drop a queued change before it is executed
+    pub fn cancel_change(env: Env, change_id: u64) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        let pending: PendingChange = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingChange(change_id))
+            .ok_or(Error::ChangeNotFound)?;
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingChange(change_id));
+
+        env.events().publish(
+            (Symbol::new(&env, "change_cancelled"),),
+            (change_id, pending.change),
+        );
+
+        Ok(())
+    }
+
+    /// Get a queued parameter change
+    pub fn get_pending_change(env: Env, change_id: u64) -> Result<PendingChange, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingChange(change_id))
+            .ok_or(Error::ChangeNotFound)
+    }
+
+    /// Get the number of ledgers a queued change waits before it can be executed
+    pub fn get_timelock_delay(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TimelockDelay)
+            .unwrap_or(DEFAULT_TIMELOCK_DELAY)
+    }
+
+    /// Get the fee tiers configured for a token
+    pub fn get_fee_tiers(env: Env, token: Address) -> Vec<FeeTier> {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeTiers(token))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Allow a token contract to be escrowed, with per-order amount bounds
+    pub fn allow_token(
+        env: Env,
+        token: Address,
+        min_amount: i128,
+        max_amount: i128,
+    ) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        if min_amount <= 0 || max_amount < min_amount {
+            return Err(Error::InvalidAmount);
+        }
+        env.storage().instance().set(
+            &DataKey::AllowedToken(token),
+            &TokenLimits {
+                min_amount,
+                max_amount,
+            },
+        );
+        Ok(())
+    }
+
+    /// Remove a token from the allowlist; orders already in flight can still settle
+    pub fn remove_token(env: Env, token: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .remove(&DataKey::AllowedToken(token));
+        Ok(())
+    }
+
+    /// Get the order amount bounds for an allowed token
+    pub fn get_token_limits(env: Env, token: Address) -> Result<TokenLimits, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedToken(token))
+            .ok_or(Error::TokenNotAllowed)
+    }
+
+    /// Update the thresholds used to extend the TTL of persistent entries
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        if threshold == 0 || threshold >= extend_to || extend_to > env.storage().max_ttl() {
+            return Err(Error::InvalidTtlConfig);
+        }
+        env.storage().instance().set(
+            &DataKey::TtlConfig,
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the thresholds used to extend the TTL of persistent entries
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::TtlConfig)
+            .unwrap_or(TtlConfig {
+                threshold: DEFAULT_TTL_THRESHOLD,
+                extend_to: DEFAULT_TTL_EXTEND_TO,
+            })
+    }
+
+    /// Update the dispute resolver address
+    pub fn set_dispute_resolver(env: Env, new_resolver: Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::DisputeResolver, &new_resolver);
+        Ok(())
+    }
+
+    /// Replace the arbiter panel; an empty panel with a zero threshold hands disputes
+    /// back to the dispute resolver
+    pub fn set_arbiters(env: Env, arbiters: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        if arbiters.is_empty() != (threshold == 0) || threshold > arbiters.len() {
+            return Err(Error::InvalidArbiterPanel);
+        }
+        for (index, arbiter) in arbiters.iter().enumerate() {
+            if arbiters.first_index_of(&arbiter) != Some(index as u32) {
+                return Err(Error::InvalidArbiterPanel);
+            }
+        }
+
+        let panel = ArbiterPanel {
+            arbiters,
+            threshold,
+        };
+        env.storage().instance().set(&DataKey::ArbiterPanel, &panel);
+
+        env.events().publish(
+            (Symbol::new(&env, "arbiters_updated"),),
+            (panel.arbiters, panel.threshold),
+        );
+
+        Ok(())
+    }
+
+    /// Get the arbiter panel
+    pub fn get_arbiters(env: Env) -> ArbiterPanel {
+        env.storage()
+            .instance()
+            .get(&DataKey::ArbiterPanel)
+            .unwrap_or(ArbiterPanel {
+                arbiters: Vec::new(&env),
+                threshold: 0,
+            })
+    }
+
+    /// Pause the contract operations
+    pub fn pause(env: Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::IsPaused, &true);
+        Ok(())
+    }
+
+    /// Unpause the contract operations
+    pub fn unpause(env: Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::IsPaused, &false);
+        Ok(())
+    }
+
+    /// Check if the contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::IsPaused)
+            .unwrap_or(false)
+    }
+
+    /// Get the current admin address
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Create a new sell order listing the seller's tokens for fiat
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_order(
+        env: Env,
+        seller: Address,
+        token: Address,
+        amount: i128,
+        fiat_currency: Symbol,
+        fiat_amount: i128,
+        rate: i128,
+        expires_at: u64,
+        payment_window: u64,
+        payment_method: String,
+    ) -> Result<u64, Error> {
+        seller.require_auth();
+
+        Self::open_order(
+            &env,
+            OrderSide::Sell,
+            seller,
+            token,
+            amount,
+            fiat_currency,
+            fiat_amount,
+            rate,
+            expires_at,
+            payment_window,
+            payment_method,
+        )
+    }
+
+    /// Create a new buy order offering fiat for a seller's tokens
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_buy_order(
+        env: Env,
+        buyer: Address,
+        token: Address,
+        amount: i128,
+        fiat_currency: Symbol,
+        fiat_amount: i128,
+        rate: i128,
+        expires_at: u64,
+        payment_window: u64,
+        payment_method: String,
+    ) -> Result<u64, Error> {
+        buyer.require_auth();
+
+        Self::open_order(
+            &env,
+            OrderSide::Buy,
+            buyer,
+            token,
+            amount,
+            fiat_currency,
+            fiat_amount,
+            rate,
+            expires_at,
+            payment_window,
+            payment_method,
+        )
+    }
+
+    /// Accept an open sell order and lock funds in escrow.
+    ///
+    /// Takes whatever amount is still open on the order; use `accept_partial`
+    /// to fill only part of it.
+    pub fn accept_order(env: Env, order_id: u64, buyer: Address) -> Result<(), Error> {
+        buyer.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        if order.side != OrderSide::Sell {
+            return Err(Error::InvalidOrderSide);
+        }
+        Self::validate_order_acceptance(&env, &order, &buyer)?;
+
+        Self::lock_escrow_funds(&env, &order)?;
+
+        order.buyer = Some(buyer.clone());
+        order.status = OrderStatus::Locked;
+        order.payment_deadline = env.ledger().timestamp() + order.payment_window;
+        order.fee_rate = Self::effective_fee_rate(&env, &order.token, order.amount)?;
+
+        Self::save_order(&env, &order);
+
+        Self::update_user_orders(&env, &buyer, order_id);
+        Self::remove_from_open_orders(&env, &order);
+
+        env.events().publish(
+            (Symbol::new(&env, "order_accepted"),),
+            (order_id, buyer.clone(), order.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Fill part of an open sell order, creating a locked child trade for the buyer.
+    ///
+    /// The fiat amount of the child is taken pro rata from the parent's fiat amount
+    /// rather than recomputed from `rate`: the rate is stored as the maker quoted it,
+    /// with no fixed scale, so only the parent's own amounts price a fill exactly as
+    /// listed. The remaining amount stays open on the parent and may not drop below
+    /// the token's minimum order size. Returns the child order id.
+    pub fn accept_partial(
+        env: Env,
+        order_id: u64,
+        buyer: Address,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        buyer.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        let mut parent = Self::load_order(&env, order_id)?;
+
+        if parent.side != OrderSide::Sell {
+            return Err(Error::InvalidOrderSide);
+        }
+        Self::validate_order_acceptance(&env, &parent, &buyer)?;
+
+        if amount <= 0 || amount >= parent.amount {
+            return Err(Error::InvalidAmount);
+        }
+        Self::validate_token_amount(&env, &parent.token, amount)?;
+        // A remainder below the minimum order size could never be filled
+        let limits = Self::get_token_limits(env.clone(), parent.token.clone())?;
+        if parent.amount - amount < limits.min_amount {
+            return Err(Error::AmountOutOfRange);
+        }
+        let fiat_amount = parent.fiat_amount * amount / parent.amount;
+        if fiat_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let order_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OrderCount)
+            .ok_or(Error::NotInitialized)?;
+        let child_id = order_count + 1;
+
+        let current_time = env.ledger().timestamp();
+        let seller = parent.seller.clone().ok_or(Error::InvalidOrderStatus)?;
+        let child = Order {
+            id: child_id,
+            side: OrderSide::Sell,
+            seller: Some(seller.clone()),
+            buyer: Some(buyer.clone()),
+            token: parent.token.clone(),
+            amount,
+            fiat_currency: parent.fiat_currency.clone(),
+            fiat_amount,
+            rate: parent.rate,
+            status: OrderStatus::Locked,
+            created_at: current_time,
+            expires_at: parent.expires_at,
+            payment_window: parent.payment_window,
+            payment_deadline: current_time + parent.payment_window,
+            payment_method: parent.payment_method.clone(),
+            parent_id: Some(order_id),
+            fee_rate: Self::effective_fee_rate(&env, &parent.token, amount)?,
+            min_completed_trades: parent.min_completed_trades,
+            closed_at: 0,
+        };
+
+        Self::lock_escrow_funds(&env, &child)?;
+
+        parent.amount -= amount;
+        parent.fiat_amount -= fiat_amount;
+
+        Self::save_order(&env, &parent);
+        Self::save_order(&env, &child);
+        env.storage()
+            .instance()
+            .set(&DataKey::OrderCount, &child_id);
+
+        let mut children: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ChildOrders(order_id))
+            .unwrap_or(Vec::new(&env));
+        children.push_back(child_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::ChildOrders(order_id), &children);
+        Self::extend_persistent_ttl(&env, &DataKey::ChildOrders(order_id));
+
+        Self::update_user_orders(&env, &seller, child_id);
+        Self::update_user_orders(&env, &buyer, child_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "order_filled"),),
+            (
+                order_id,
+                child_id,
+                buyer,
+                amount,
+                fiat_amount,
+                parent.amount,
+            ),
+        );
+
+        Ok(child_id)
+    }
+
+    /// Fill an open buy order, locking the accepting seller's tokens in escrow
+    pub fn accept_buy_order(env: Env, order_id: u64, seller: Address) -> Result<(), Error> {
+        seller.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        if order.side != OrderSide::Buy {
+            return Err(Error::InvalidOrderSide);
+        }
+        Self::validate_order_acceptance(&env, &order, &seller)?;
+
+        order.seller = Some(seller.clone());
+        Self::lock_escrow_funds(&env, &order)?;
+
+        order.status = OrderStatus::Locked;
+        order.payment_deadline = env.ledger().timestamp() + order.payment_window;
+        order.fee_rate = Self::effective_fee_rate(&env, &order.token, order.amount)?;
+
+        Self::save_order(&env, &order);
+
+        Self::update_user_orders(&env, &seller, order_id);
+        Self::remove_from_open_orders(&env, &order);
+
+        env.events().publish(
+            (Symbol::new(&env, "order_accepted"),),
+            (order_id, seller, order.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw an open order that has not been accepted yet
+    pub fn cancel_order(env: Env, order_id: u64) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        let maker = Self::order_maker(&order)?;
+        maker.require_auth();
+
+        if order.status != OrderStatus::Open {
+            return Err(Error::InvalidOrderStatus);
+        }
+
+        order.status = OrderStatus::Cancelled;
+        order.closed_at = env.ledger().timestamp();
+
+        Self::save_order(&env, &order);
+
+        Self::remove_from_open_orders(&env, &order);
+        Self::update_reputation(&env, &maker, |reputation| reputation.cancelled_orders += 1);
+
+        env.events()
+            .publish((Symbol::new(&env, "order_cancelled"),), (order_id, maker));
+
+        Ok(())
+    }
+
+    /// Close an open order whose listing has expired, callable by anyone.
+    ///
+    /// Unlike `cancel_order`, this does not count against the maker's reputation.
+    pub fn expire_order(env: Env, order_id: u64) -> Result<(), Error> {
+        let mut order = Self::load_order(&env, order_id)?;
+
+        if order.status != OrderStatus::Open {
+            return Err(Error::InvalidOrderStatus);
+        }
+        if env.ledger().timestamp() <= order.expires_at {
+            return Err(Error::InvalidExpiration);
+        }
+
+        let maker = Self::order_maker(&order)?;
+        order.status = OrderStatus::Cancelled;
+        order.closed_at = env.ledger().timestamp();
+
+        Self::save_order(&env, &order);
+
+        Self::remove_from_open_orders(&env, &order);
+
+        env.events()
+            .publish((Symbol::new(&env, "order_expired"),), (order_id, maker));
+
+        Ok(())
+    }
+
+    /// Return locked funds to the seller once the payment window has passed
+    /// without the buyer marking the payment as sent
+    pub fn reclaim_expired(env: Env, order_id: u64) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        let seller = order.seller.clone().ok_or(Error::InvalidOrderStatus)?;
+        seller.require_auth();
+
+        if order.status != OrderStatus::Locked {
+            return Err(Error::InvalidOrderStatus);
+        }
+        if env.ledger().timestamp() <= order.payment_deadline {
+            return Err(Error::PaymentWindowActive);
+        }
+
+        Self::transfer_from_escrow(&env, &order.token, &seller, order.amount);
+
+        order.status = OrderStatus::Cancelled;
+        order.closed_at = env.ledger().timestamp();
+
+        Self::save_order(&env, &order);
+
+        // The buyer let the payment window lapse
+        if let Some(buyer) = &order.buyer {
+            Self::update_reputation(&env, buyer, |reputation| reputation.abandoned_trades += 1);
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "order_reclaimed"),),
+            (order_id, seller, order.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Buyer confirms that the fiat payment has been sent to the seller
+    pub fn mark_payment_sent(env: Env, order_id: u64) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        let buyer = order.buyer.clone().ok_or(Error::InvalidOrderStatus)?;
+        buyer.require_auth();
+
+        if order.status != OrderStatus::Locked {
+            return Err(Error::InvalidOrderStatus);
+        }
+
+        order.status = OrderStatus::PaymentSent;
+
+        Self::save_order(&env, &order);
+
+        env.events().publish(
+            (Symbol::new(&env, "payment_sent"),),
+            (order_id, buyer, order.fiat_amount),
+        );
+
+        Ok(())
+    }
+
+    /// Seller confirms receipt of the fiat payment and releases the escrowed tokens
+    pub fn release(env: Env, order_id: u64) -> Result<(), Error> {
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        let seller = order.seller.clone().ok_or(Error::InvalidOrderStatus)?;
+        seller.require_auth();
+
+        if order.status != OrderStatus::Locked && order.status != OrderStatus::PaymentSent {
+            return Err(Error::InvalidOrderStatus);
+        }
+        let buyer = order.buyer.clone().ok_or(Error::InvalidOrderStatus)?;
+
+        let fee_treasury: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeTreasury)
+            .ok_or(Error::NotInitialized)?;
+
+        let fee = Self::calculate_fee(order.amount, order.fee_rate);
+        let payout = order.amount - fee;
+
+        Self::transfer_from_escrow(&env, &order.token, &buyer, payout);
+        Self::transfer_from_escrow(&env, &order.token, &fee_treasury, fee);
+
+        order.status = OrderStatus::Completed;
+        order.closed_at = env.ledger().timestamp();
+
+        Self::save_order(&env, &order);
+
+        let locked_at = order.payment_deadline - order.payment_window;
+        let release_time = env.ledger().timestamp().saturating_sub(locked_at);
+        Self::update_reputation(&env, &seller, |reputation| {
+            reputation.completed_trades += 1;
+            reputation.released_trades += 1;
+            reputation.total_release_time += release_time;
+        });
+        Self::update_reputation(&env, &buyer, |reputation| reputation.completed_trades += 1);
+
+        env.events().publish(
+            (Symbol::new(&env, "order_released"),),
+            (order_id, buyer, payout, fee),
+        );
+
+        Ok(())
+    }
+
+    /// Escalate a locked order to the dispute resolver, callable by buyer or seller
+    pub fn raise_dispute(env: Env, order_id: u64, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        let mut order = Self::load_order(&env, order_id)?;
+
+        if order.status != OrderStatus::Locked && order.status != OrderStatus::PaymentSent {
+            return Err(Error::InvalidOrderStatus);
+        }
+        if order.seller.as_ref() != Some(&caller) && order.buyer.as_ref() != Some(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let raised_at = env.ledger().timestamp();
+        let dispute = Dispute {
+            order_id,
+            raised_by: caller.clone(),
+            previous_status: order.status.clone(),
+            raised_at,
+            outcome: DisputeOutcome::Pending,
+        };
+
+        order.status = OrderStatus::Disputed;
+
+        Self::save_order(&env, &order);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(order_id), &dispute);
+        Self::extend_persistent_ttl(&env, &DataKey::Dispute(order_id));
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_raised"),),
+            (order_id, caller, dispute.previous_status, raised_at),
+        );
+
+        Ok(())
+    }
+
+    /// Settle a disputed order, sending `buyer_share_bps` of the escrow after fees to the buyer
+    /// and the rest back to the seller
+    pub fn resolve_dispute(env: Env, order_id: u64, buyer_share_bps: u32) -> Result<(), Error> {
+        let resolver: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::DisputeResolver)
+            .ok_or(Error::NotInitialized)?;
+        resolver.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        if Self::get_arbiters(env.clone()).threshold > 0 {
+            return Err(Error::ArbiterPanelActive);
+        }
+
+        Self::settle_dispute(&env, order_id, resolver, buyer_share_bps)
+    }
+
+    /// Attach an evidence hash to a disputed order, callable by buyer or seller
+    pub fn submit_evidence(
+        env: Env,
+        order_id: u64,
+        caller: Address,
+        hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let order = Self::load_order(&env, order_id)?;
+        if order.status != OrderStatus::Disputed {
+            return Err(Error::InvalidOrderStatus);
+        }
+        if order.seller.as_ref() != Some(&caller) && order.buyer.as_ref() != Some(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        let key = DataKey::DisputeEvidence(order_id);
+        let mut evidence: Vec<Evidence> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        if evidence.len() >= MAX_DISPUTE_EVIDENCE {
+            return Err(Error::EvidenceLimitReached);
+        }
+        evidence.push_back(Evidence {
+            submitted_by: caller.clone(),
+            hash: hash.clone(),
+            submitted_at: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&key, &evidence);
+        Self::extend_persistent_ttl(&env, &key);
+
+        env.events().publish(
+            (Symbol::new(&env, "evidence_submitted"),),
+            (order_id, caller, hash),
+        );
+
+        Ok(())
+    }
+
+    /// Cast or replace an arbiter's vote on the buyer's share of a disputed order.
+    ///
+    /// The dispute is settled as soon as `threshold` current arbiters agree on the same share;
+    /// arbiters can change their vote so a split panel is not stuck for good.
+    pub fn vote_dispute(
+        env: Env,
+        order_id: u64,
+        arbiter: Address,
+        buyer_share_bps: u32,
+    ) -> Result<(), Error> {
+        arbiter.require_auth();
+
+        Self::require_not_paused(&env)?;
+
+        let panel = Self::get_arbiters(env.clone());
+        if !panel.arbiters.contains(&arbiter) {
+            return Err(Error::NotArbiter);
+        }
+        if buyer_share_bps > 10_000 {
+            return Err(Error::InvalidDisputeShare);
+        }
+
+        let order = Self::load_order(&env, order_id)?;
+        if order.status != OrderStatus::Disputed {
+            return Err(Error::InvalidOrderStatus);
+        }
+
+        let key = DataKey::DisputeVotes(order_id);
+        let mut votes: Map<Address, u32> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Map::new(&env));
+        let previous_share = votes.get(arbiter.clone());
+        votes.set(arbiter.clone(), buyer_share_bps);
+        env.storage().persistent().set(&key, &votes);
+        Self::extend_persistent_ttl(&env, &key);
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_vote"),),
+            (order_id, arbiter.clone(), buyer_share_bps, previous_share),
+        );
+
+        // Votes from arbiters removed since they voted no longer count
+        let agreeing = votes
+            .iter()
+            .filter(|(voter, share)| *share == buyer_share_bps && panel.arbiters.contains(voter))
+            .count() as u32;
+        if agreeing >= panel.threshold {
+            Self::settle_dispute(&env, order_id, arbiter, buyer_share_bps)?;
+        }
+
+        Ok(())
+    }
+
+    /// Quote the platform fee for an order: the rate locked in at acceptance, or the
+    /// rate it would get if accepted now while it is still open
+    pub fn quote_fee(env: Env, order_id: u64) -> Result<i128, Error> {
+        let order: Order = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .ok_or(Error::OrderNotFound)?;
+
+        let fee_rate = if order.status == OrderStatus::Open {
+            Self::effective_fee_rate(&env, &order.token, order.amount)?
+        } else {
+            order.fee_rate
+        };
+
+        Ok(Self::calculate_fee(order.amount, fee_rate))
+    }
+
+    /// Get the dispute record for an order
+    pub fn get_dispute(env: Env, order_id: u64) -> Result<Dispute, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dispute(order_id))
+            .ok_or(Error::DisputeNotFound)
+    }
+
+    /// Get the evidence hashes attached to a dispute
+    pub fn get_dispute_evidence(env: Env, order_id: u64) -> Vec<Evidence> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DisputeEvidence(order_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the arbiter votes cast on a dispute so far
+    pub fn get_dispute_votes(env: Env, order_id: u64) -> Map<Address, u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DisputeVotes(order_id))
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Require takers of an open order to have at least `min_completed_trades` completed
+    /// trades; zero removes the requirement
+    pub fn set_min_reputation(
+        env: Env,
+        order_id: u64,
+        min_completed_trades: u32,
+    ) -> Result<(), Error> {
+        let mut order = Self::load_order(&env, order_id)?;
+
+        let maker = Self::order_maker(&order)?;
+        maker.require_auth();
+
+        if order.status != OrderStatus::Open {
+            return Err(Error::InvalidOrderStatus);
+        }
+
+        order.min_completed_trades = min_completed_trades;
+        Self::save_order(&env, &order);
+
+        Ok(())
+    }
+
+    /// Get the trade history counters of an address
+    pub fn get_reputation(env: Env, address: Address) -> Reputation {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Reputation(address))
+            .unwrap_or_default()
+    }
+
+    /// Keep an order and its dispute and partial fill records from being archived,
+    /// callable by anyone
+    pub fn extend_order_ttl(env: Env, order_id: u64) -> Result<(), Error> {
+        Self::load_order(&env, order_id)?;
+
+        for key in [
+            DataKey::Dispute(order_id),
+            DataKey::DisputeEvidence(order_id),
+            DataKey::DisputeVotes(order_id),
+            DataKey::ChildOrders(order_id),
+        ] {
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent_ttl(&env, &key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drop orders that finished more than `older_than_days` ago from a user's order list.
+    ///
+    /// The orders themselves are kept; only the user's index shrinks. Returns the
+    /// number of entries removed.
+    pub fn prune_user_orders(env: Env, user: Address, older_than_days: u32) -> Result<u32, Error> {
+        user.require_auth();
+
+        let key = DataKey::UserOrders(user);
+        let order_ids: Vec<u64> = match env.storage().persistent().get(&key) {
+            Some(order_ids) => order_ids,
+            None => return Ok(0),
+        };
+
+        let cutoff = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(older_than_days as u64 * DAY_IN_SECONDS);
+
+        let mut kept = Vec::new(&env);
+        for order_id in order_ids.iter() {
+            let order: Option<Order> = env.storage().persistent().get(&DataKey::Order(order_id));
+            let prunable = match order {
+                Some(order) => order.status.is_final() && order.closed_at < cutoff,
+                None => true,
+            };
+            if !prunable {
+                kept.push_back(order_id);
+            }
+        }
+
+        let removed = order_ids.len() - kept.len();
+        if removed > 0 {
+            env.storage().persistent().set(&key, &kept);
+        }
+        Self::extend_persistent_ttl(&env, &key);
+
+        Ok(removed)
+    }
+
+    /// Get an order by id
+    pub fn get_order(env: Env, order_id: u64) -> Result<Order, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .ok_or(Error::OrderNotFound)
+    }
+
+    /// List the orders a user has created or accepted, oldest first
+    pub fn get_user_orders(env: Env, user: Address, offset: u32, limit: u32) -> Vec<Order> {
+        let order_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserOrders(user))
+            .unwrap_or(Vec::new(&env));
+
+        let end = offset
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(order_ids.len());
+
+        let mut orders = Vec::new(&env);
+        for i in offset..end {
+            let order_id = order_ids.get_unchecked(i);
+            if let Some(order) = env.storage().persistent().get(&DataKey::Order(order_id)) {
+                orders.push_back(order);
+            }
+        }
+        orders
+    }
+
+    /// List the child trades created by partial fills of an order
+    pub fn get_child_orders(env: Env, order_id: u64) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ChildOrders(order_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// List unexpired open orders on one side of a token and fiat currency pair, oldest first.
+    ///
+    /// Walks up to `limit` listed orders after the order id `cursor`, or from the oldest
+    /// when it is `None`. Fails with `OrderNotFound` if the cursor order has left the list
+    /// since the previous page was read.
+    pub fn get_open_orders(
+        env: Env,
+        side: OrderSide,
+        token: Address,
+        fiat_currency: Symbol,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<OpenOrderPage, Error> {
+        let mut next = match cursor {
+            Some(order_id) => {
+                Self::open_order_link(&env, order_id)
+                    .ok_or(Error::OrderNotFound)?
+                    .next
+            }
+            None => env
+                .storage()
+                .persistent()
+                .get::<_, OpenOrderList>(&DataKey::OpenOrderList(side, token, fiat_currency))
+                .and_then(|list| list.head),
+        };
+
+        let current_time = env.ledger().timestamp();
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let mut visited = 0u32;
+        let mut last_visited = cursor;
+        let mut orders = Vec::new(&env);
+        while let Some(order_id) = next {
+            if visited >= limit {
+                break;
+            }
+            visited += 1;
+            last_visited = Some(order_id);
+            next = Self::open_order_link(&env, order_id).and_then(|link| link.next);
+
+            let order: Order = match env.storage().persistent().get(&DataKey::Order(order_id)) {
+                Some(order) => order,
+                None => continue,
+            };
+            // Expired listings stay linked until someone calls `expire_order`
+            if order.status == OrderStatus::Open && current_time <= order.expires_at {
+                orders.push_back(order);
+            }
+        }
+
+        Ok(OpenOrderPage {
+            orders,
+            next_cursor: next.and(last_visited),
+        })
+    }
+
+    /// Reject state-changing calls while the contract is paused or its stored orders
+    /// are still in an older layout
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::version(env.clone()) < CONTRACT_VERSION {
+            return Err(Error::MigrationPending);
+        }
+        let is_paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::IsPaused)
+            .unwrap_or(false);
+        if is_paused {
+            return Err(Error::ContractPaused);
+        }
+        Ok(())
+    }
+
+    /// Validate and store a new open order on behalf of its maker
+    #[allow(clippy::too_many_arguments)]
+    fn open_order(
+        env: &Env,
+        side: OrderSide,
+        maker: Address,
+        token: Address,
+        amount: i128,
+        fiat_currency: Symbol,
+        fiat_amount: i128,
+        rate: i128,
+        expires_at: u64,
+        payment_window: u64,
+        payment_method: String,
+    ) -> Result<u64, Error> {
+        Self::require_not_paused(env)?;
+
+        if amount <= 0 || fiat_amount <= 0 || rate <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if expires_at <= current_time {
+            return Err(Error::InvalidExpiration);
+        }
+
+        if payment_window == 0 {
+            return Err(Error::InvalidPaymentWindow);
+        }
+
+        Self::validate_token_amount(env, &token, amount)?;
+
+        if Self::open_order_count(env, &maker) >= MAX_OPEN_ORDERS_PER_MAKER {
+            return Err(Error::TooManyOpenOrders);
+        }
+
+        let order_count: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OrderCount)
+            .ok_or(Error::NotInitialized)?;
+        let order_id = order_count + 1;
+
+        let (seller, buyer) = match side {
+            OrderSide::Sell => (Some(maker.clone()), None),
+            OrderSide::Buy => (None, Some(maker.clone())),
+        };
+
+        let order = Order {
+            id: order_id,
+            side: side.clone(),
+            seller,
+            buyer,
+            token: token.clone(),
+            amount,
+            fiat_currency,
+            fiat_amount,
+            rate,
+            status: OrderStatus::Open,
+            created_at: current_time,
+            expires_at,
+            payment_window,
+            payment_deadline: 0,
+            payment_method,
+            parent_id: None,
+            fee_rate: 0,
+            min_completed_trades: 0,
+            closed_at: 0,
+        };
+
+        Self::save_order(env, &order);
+        env.storage()
+            .instance()
+            .set(&DataKey::OrderCount, &order_id);
+
+        Self::update_user_orders(env, &maker, order_id);
+        Self::add_to_open_orders(env, &order);
+
+        env.events().publish(
+            (Symbol::new(env, "order_created"),),
+            (order_id, maker, token, amount, side),
+        );
+
+        Ok(order_id)
+    }
+
+    /// Validate that an order can be accepted by the counterparty taking it
+    fn validate_order_acceptance(env: &Env, order: &Order, taker: &Address) -> Result<(), Error> {
+        if order.status != OrderStatus::Open {
+            return Err(Error::InvalidOrderStatus);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if current_time > order.expires_at {
+            return Err(Error::OrderExpired);
+        }
+
+        if order.seller.as_ref() == Some(taker) || order.buyer.as_ref() == Some(taker) {
+            return Err(Error::CannotAcceptOwnOrder);
+        }
+
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::AllowedToken(order.token.clone()))
+        {
+            return Err(Error::TokenNotAllowed);
+        }
+
+        if order.min_completed_trades > 0
+            && Self::get_reputation(env.clone(), taker.clone()).completed_trades
+                < order.min_completed_trades
+        {
+            return Err(Error::ReputationTooLow);
+        }
+
+        Ok(())
+    }
+
+    /// Check that a token is allowlisted and the amount is within its order bounds
+    fn validate_token_amount(env: &Env, token: &Address, amount: i128) -> Result<(), Error> {
+        let limits: TokenLimits = env
+            .storage()
+            .instance()
+            .get(&DataKey::AllowedToken(token.clone()))
+            .ok_or(Error::TokenNotAllowed)?;
+
+        if amount < limits.min_amount || amount > limits.max_amount {
+            return Err(Error::AmountOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    /// Lock the seller's crypto funds in the escrow contract
+    fn lock_escrow_funds(env: &Env, order: &Order) -> Result<(), Error> {
+        let seller = order.seller.as_ref().ok_or(Error::InvalidOrderStatus)?;
+        let token_client = token::Client::new(env, &order.token);
+
+        token_client.transfer(seller, &env.current_contract_address(), &order.amount);
+
+        Ok(())
+    }
+
+    /// The address that created an order: the seller of a sell order or the buyer of a buy order
+    fn order_maker(order: &Order) -> Result<Address, Error> {
+        let maker = match order.side {
+            OrderSide::Sell => order.seller.clone(),
+            OrderSide::Buy => order.buyer.clone(),
+        };
+        maker.ok_or(Error::InvalidOrderStatus)
+    }
+
+    /// Pay out tokens held by the escrow, skipping zero amounts
+    fn transfer_from_escrow(env: &Env, token: &Address, to: &Address, amount: i128) {
+        if amount > 0 {
+            token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+        }
+    }
+
+    /// Fee rate for a trade of `amount` tokens: the matching tier for the token if one
+    /// is configured, otherwise the global fee rate
+    fn effective_fee_rate(env: &Env, token: &Address, amount: i128) -> Result<u32, Error> {
+        let tiers: Vec<FeeTier> = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeTiers(token.clone()))
+            .unwrap_or(Vec::new(env));
+
+        let mut tier_rate = None;
+        for tier in tiers.iter() {
+            if amount < tier.min_amount {
+                break;
+            }
+            tier_rate = Some(tier.fee_rate);
+        }
+
+        match tier_rate {
+            Some(fee_rate) => Ok(fee_rate),
+            None => env
+                .storage()
+                .instance()
+                .get(&DataKey::FeeRate)
+                .ok_or(Error::NotInitialized),
+        }
+    }
+
+    /// Platform fee for an amount at the given rate in basis points
+    fn calculate_fee(amount: i128, fee_rate: u32) -> i128 {
+        amount * fee_rate as i128 / 10_000
+    }
+
+    /// Append an order to the open order list for its token and fiat currency
+    fn add_to_open_orders(env: &Env, order: &Order) {
+        let list_key = DataKey::OpenOrderList(
+            order.side.clone(),
+            order.token.clone(),
+            order.fiat_currency.clone(),
+        );
+        let mut list: OpenOrderList =
+            env.storage()
+                .persistent()
+                .get(&list_key)
+                .unwrap_or(OpenOrderList {
+                    head: None,
+                    tail: None,
+                });
+
+        match list.tail {
+            Some(tail) => {
+                Self::update_open_order_link(env, tail, |link| link.next = Some(order.id))
+            }
+            None => list.head = Some(order.id),
+        }
+        let link_key = DataKey::OpenOrderLink(order.id);
+        env.storage().persistent().set(
+            &link_key,
+            &OpenOrderLink {
+                prev: list.tail,
+                next: None,
+            },
+        );
+        Self::extend_persistent_ttl(env, &link_key);
+
+        list.tail = Some(order.id);
+        env.storage().persistent().set(&list_key, &list);
+        Self::extend_persistent_ttl(env, &list_key);
+
+        if let Ok(maker) = Self::order_maker(order) {
+            let count = Self::open_order_count(env, &maker);
+            Self::set_open_order_count(env, &maker, count + 1);
+        }
+    }
+
+    /// Unlink an order from the open order list once it is no longer open
+    fn remove_from_open_orders(env: &Env, order: &Order) {
+        let link = match Self::open_order_link(env, order.id) {
+            Some(link) => link,
+            None => return,
+        };
+        env.storage()
+            .persistent()
+            .remove(&DataKey::OpenOrderLink(order.id));
+
+        let list_key = DataKey::OpenOrderList(
+            order.side.clone(),
+            order.token.clone(),
+            order.fiat_currency.clone(),
+        );
+        let mut list: OpenOrderList = match env.storage().persistent().get(&list_key) {
+            Some(list) => list,
+            None => return,
+        };
+        match link.prev {
+            Some(prev) => Self::update_open_order_link(env, prev, |prev| prev.next = link.next),
+            None => list.head = link.next,
+        }
+        match link.next {
+            Some(next) => Self::update_open_order_link(env, next, |next| next.prev = link.prev),
+            None => list.tail = link.prev,
+        }
+        if list.head.is_some() {
+            env.storage().persistent().set(&list_key, &list);
+            Self::extend_persistent_ttl(env, &list_key);
+        } else {
+            env.storage().persistent().remove(&list_key);
+        }
+
+        if let Ok(maker) = Self::order_maker(order) {
+            let count = Self::open_order_count(env, &maker);
+            Self::set_open_order_count(env, &maker, count.saturating_sub(1));
+        }
+    }
+
+    fn open_order_link(env: &Env, order_id: u64) -> Option<OpenOrderLink> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OpenOrderLink(order_id))
+    }
+
+    /// Apply `update` to the list links of an open order
+    fn update_open_order_link(env: &Env, order_id: u64, update: impl FnOnce(&mut OpenOrderLink)) {
+        if let Some(mut link) = Self::open_order_link(env, order_id) {
+            update(&mut link);
+            let key = DataKey::OpenOrderLink(order_id);
+            env.storage().persistent().set(&key, &link);
+            Self::extend_persistent_ttl(env, &key);
+        }
+    }
+
+    /// Number of orders a maker currently has listed
+    fn open_order_count(env: &Env, maker: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OpenOrderCount(maker.clone()))
+            .unwrap_or(0)
+    }
+
+    fn set_open_order_count(env: &Env, maker: &Address, count: u32) {
+        let key = DataKey::OpenOrderCount(maker.clone());
+        if count == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &count);
+            Self::extend_persistent_ttl(env, &key);
+        }
+    }
+
+    /// Update the user's order list to include the new order
+    fn update_user_orders(env: &Env, user: &Address, order_id: u64) {
+        let mut user_orders: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserOrders(user.clone()))
+            .unwrap_or(Vec::new(env));
+
+        user_orders.push_back(order_id);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::UserOrders(user.clone()), &user_orders);
+        Self::extend_persistent_ttl(env, &DataKey::UserOrders(user.clone()));
+    }
+
+    /// Store a parameter change that becomes executable after the timelock delay
+    fn queue_change(env: &Env, change: ParamChange) -> Result<u64, Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        let change_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ChangeCount)
+            .unwrap_or(0)
+            + 1;
+        let proposed_at = env.ledger().sequence();
+        let pending = PendingChange {
+            id: change_id,
+            change,
+            proposed_at,
+            executable_at: proposed_at.saturating_add(Self::get_timelock_delay(env.clone())),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::ChangeCount, &change_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingChange(change_id), &pending);
+        // Keep the instance alive at least until the change can be executed
+        Self::extend_instance_ttl(env);
+
+        env.events().publish(
+            (Symbol::new(env, "change_proposed"),),
+            (change_id, pending.change, pending.executable_at),
+        );
+
+        Ok(change_id)
+    }
+
+    /// Pay out a disputed order, sending `buyer_share_bps` of the escrow after fees to the
+    /// buyer and the rest back to the seller
+    fn settle_dispute(
+        env: &Env,
+        order_id: u64,
+        resolver: Address,
+        buyer_share_bps: u32,
+    ) -> Result<(), Error> {
+        if buyer_share_bps > 10_000 {
+            return Err(Error::InvalidDisputeShare);
+        }
+
+        let mut order = Self::load_order(env, order_id)?;
+        if order.status != OrderStatus::Disputed {
+            return Err(Error::InvalidOrderStatus);
+        }
+        let buyer = order.buyer.clone().ok_or(Error::InvalidOrderStatus)?;
+        let seller = order.seller.clone().ok_or(Error::InvalidOrderStatus)?;
+
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(order_id))
+            .ok_or(Error::DisputeNotFound)?;
+
+        let fee_treasury: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeTreasury)
+            .ok_or(Error::NotInitialized)?;
+
+        let fee = Self::calculate_fee(order.amount, order.fee_rate);
+        let net_amount = order.amount - fee;
+        let buyer_amount = net_amount * buyer_share_bps as i128 / 10_000;
+        let seller_amount = net_amount - buyer_amount;
+
+        Self::transfer_from_escrow(env, &order.token, &buyer, buyer_amount);
+        Self::transfer_from_escrow(env, &order.token, &seller, seller_amount);
+        Self::transfer_from_escrow(env, &order.token, &fee_treasury, fee);
+
+        let resolved_at = env.ledger().timestamp();
+        dispute.outcome = DisputeOutcome::Resolved(DisputeResolution {
+            resolver: resolver.clone(),
+            buyer_share_bps,
+            buyer_amount,
+            seller_amount,
+            fee,
+            resolved_at,
+        });
+        order.status = OrderStatus::Resolved;
+        order.closed_at = resolved_at;
+
+        Self::save_order(env, &order);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Dispute(order_id), &dispute);
+        Self::extend_persistent_ttl(env, &DataKey::Dispute(order_id));
+
+        // An even split counts as neither a win nor a loss
+        if buyer_share_bps != 5_000 {
+            let (winner, loser) = if buyer_share_bps > 5_000 {
+                (&buyer, &seller)
+            } else {
+                (&seller, &buyer)
+            };
+            Self::update_reputation(env, winner, |reputation| reputation.disputes_won += 1);
+            Self::update_reputation(env, loser, |reputation| reputation.disputes_lost += 1);
+        }
+
+        env.events().publish(
+            (Symbol::new(env, "dispute_resolved"),),
+            (
+                order_id,
+                resolver,
+                buyer_share_bps,
+                buyer_amount,
+                seller_amount,
+                fee,
+                resolved_at,
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Apply `update` to the reputation record of an address
+    fn update_reputation(env: &Env, address: &Address, update: impl FnOnce(&mut Reputation)) {
+        let key = DataKey::Reputation(address.clone());
+        let mut reputation: Reputation = env.storage().persistent().get(&key).unwrap_or_default();
+        update(&mut reputation);
+        env.storage().persistent().set(&key, &reputation);
+        Self::extend_persistent_ttl(env, &key);
+    }
+
+    /// Rewrite a single order stored in an older layout
+    fn migrate_order(env: &Env, order_id: u64, from_version: u32) -> Result<(), Error> {
+        let key = DataKey::Order(order_id);
+        // Each layout change adds an arm decoding the previous struct into `Order`
+        let order: Option<Order> = match from_version {
+            1 => env
+                .storage()
+                .persistent()
+                .get::<_, OrderV1>(&key)
+                .map(|order| OrderV2::from(order).into()),
+            2 | 3 => env
+                .storage()
+                .persistent()
+                .get::<_, OrderV2>(&key)
+                .map(Order::from),
+            // Contracts deployed before versioning stored orders without a side, payment
+            // window or fee rate, which cannot be filled in after the fact
+            _ => return Err(Error::UnsupportedVersion),
+        };
+        let Some(mut order) = order else {
+            return Ok(());
+        };
+
+        // Closing times are only known from version 4 on, so finished orders count as
+        // closed at the migration and are kept for the full pruning period
+        if order.status.is_final() {
+            order.closed_at = env.ledger().timestamp();
+        }
+        Self::save_order(env, &order);
+
+        // Version 3 moved open orders from one vector per pair into a linked list;
+        // orders are visited oldest first, so appending keeps the listing order
+        if from_version < 3
+            && order.status == OrderStatus::Open
+            && Self::open_order_link(env, order_id).is_none()
+        {
+            let legacy_key = DataKey::OpenOrders(
+                order.side.clone(),
+                order.token.clone(),
+                order.fiat_currency.clone(),
+            );
+            if env.storage().persistent().has(&legacy_key) {
+                env.storage().persistent().remove(&legacy_key);
+            }
+            Self::add_to_open_orders(env, &order);
+        }
+
+        Ok(())
+    }
+
+    /// Load an order and extend its TTL
+    fn load_order(env: &Env, order_id: u64) -> Result<Order, Error> {
+        let order: Order = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .ok_or(Error::OrderNotFound)?;
+        Self::extend_persistent_ttl(env, &DataKey::Order(order_id));
+        Ok(order)
+    }
+
+    /// Store an order, extending its TTL and the contract instance's
+    fn save_order(env: &Env, order: &Order) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order.id), order);
+        Self::extend_persistent_ttl(env, &DataKey::Order(order.id));
+        Self::extend_instance_ttl(env);
+    }
+
+    /// Extend the TTL of the contract instance and its configuration
+    fn extend_instance_ttl(env: &Env) {
+        let config = Self::get_ttl_config(env.clone());
+        env.storage()
+            .instance()
+            .extend_ttl(config.threshold, config.extend_to);
+    }
+
+    /// Extend the TTL of a persistent entry using the configured thresholds
+    fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+        let config = Self::get_ttl_config(env.clone());
+        env.storage()
+            .persistent()
+            .extend_ttl(key, config.threshold, config.extend_to);
+    }
+}
+
+mod test;
@@ -1,129 +1,4592 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::{Address as _, Env as _};
-    use soroban_sdk::{Address, Env};
-
-    fn create_env() -> Env {
-        Env::default()
+#![cfg(test)]
+
+use super::*;
+extern crate std;
+
+use soroban_sdk::testutils::{
+    storage::Persistent as _, Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger,
+    MockAuth, MockAuthInvoke,
+};
+use soroban_sdk::{Address, Env, IntoVal, InvokeError};
+
+fn create_env() -> Env {
+    Env::default()
+}
+
+fn create_addresses(env: &Env) -> (Address, Address, Address, Address) {
+    (
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    )
+}
+
+fn create_token(env: &Env, admin: &Address, user: &Address, amount: i128) -> Address {
+    let sac = env.register_stellar_asset_contract_v2(admin.clone());
+    token::StellarAssetClient::new(env, &sac.address())
+        .mock_all_auths()
+        .mint(user, &amount);
+    sac.address()
+}
+
+fn allow_token(env: &Env, contract_id: &Address, token: &Address) {
+    env.as_contract(contract_id, || {
+        EscrowContract::allow_token(env.clone(), token.clone(), 1, 1_000_000_000).unwrap();
+    });
+}
+
+/// Queue a fee tier change and execute it once the timelock has elapsed
+fn set_fee_tiers(env: &Env, contract_id: &Address, token: &Address, tiers: Vec<FeeTier>) {
+    let pending = env.as_contract(contract_id, || {
+        let change_id =
+            EscrowContract::propose_fee_tiers(env.clone(), token.clone(), tiers).unwrap();
+        EscrowContract::get_pending_change(env.clone(), change_id).unwrap()
+    });
+    env.ledger().set_sequence_number(pending.executable_at);
+    env.as_contract(contract_id, || {
+        EscrowContract::execute_change(env.clone(), pending.id).unwrap();
+    });
+}
+
+fn create_mock_order(
+    env: &Env,
+    seller: &Address,
+    token: &Address,
+    order_id: u64,
+    status: OrderStatus,
+    expires_at: u64,
+) -> Order {
+    Order {
+        id: order_id,
+        side: OrderSide::Sell,
+        seller: Some(seller.clone()),
+        buyer: None,
+        token: token.clone(),
+        amount: 1000,
+        fiat_currency: Symbol::new(env, "USD"),
+        fiat_amount: 100,
+        rate: 10,
+        status,
+        created_at: env.ledger().timestamp(),
+        expires_at,
+        payment_window: 1800,
+        payment_deadline: 0,
+        payment_method: String::from_str(env, "Bank Transfer"),
+        parent_id: None,
+        fee_rate: 0,
+        min_completed_trades: 0,
+        closed_at: 0,
+    }
+}
+
+fn create_locked_order(
+    env: &Env,
+    contract_id: &Address,
+    seller: &Address,
+    buyer: &Address,
+    token: &Address,
+) -> u64 {
+    let expires_at = env.ledger().timestamp() + 3600;
+    env.as_contract(contract_id, || {
+        let order_id = EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(env, "Bank Transfer"),
+        )
+        .unwrap();
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone()).unwrap();
+        order_id
+    })
+}
+
+fn create_open_order(
+    env: &Env,
+    contract_id: &Address,
+    seller: &Address,
+    token: &Address,
+    fiat_currency: &str,
+    expires_at: u64,
+) -> u64 {
+    env.as_contract(contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(env, fiat_currency),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(env, "Bank Transfer"),
+        )
+        .unwrap()
+    })
+}
+
+fn open_order_ids(
+    env: &Env,
+    contract_id: &Address,
+    side: OrderSide,
+    token: &Address,
+    fiat_currency: &str,
+    cursor: Option<u64>,
+    limit: u32,
+) -> Vec<u64> {
+    let page = env.as_contract(contract_id, || {
+        EscrowContract::get_open_orders(
+            env.clone(),
+            side,
+            token.clone(),
+            Symbol::new(env, fiat_currency),
+            cursor,
+            limit,
+        )
+        .unwrap()
+    });
+    let mut ids = Vec::new(env);
+    for order in page.orders.iter() {
+        ids.push_back(order.id);
+    }
+    ids
+}
+
+fn create_open_buy_order(
+    env: &Env,
+    contract_id: &Address,
+    buyer: &Address,
+    token: &Address,
+    expires_at: u64,
+) -> u64 {
+    env.as_contract(contract_id, || {
+        EscrowContract::create_buy_order(
+            env.clone(),
+            buyer.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(env, "NGN"),
+            1_500_000,
+            1500,
+            expires_at,
+            1800,
+            String::from_str(env, "Bank Transfer"),
+        )
+        .unwrap()
+    })
+}
+
+#[test]
+fn test_initialize() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+    });
+    assert!(result.is_ok());
+
+    let stored_admin = env.as_contract(&contract_id, || {
+        EscrowContract::get_admin(env.clone()).unwrap()
+    });
+    assert_eq!(stored_admin, admin);
+
+    let is_paused = env.as_contract(&contract_id, || EscrowContract::is_paused(env.clone()));
+    assert!(!is_paused);
+}
+
+#[test]
+fn test_prevent_double_initialization() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+    });
+    assert_eq!(result, Err(Error::AlreadyInitialized));
+}
+
+#[test]
+fn test_fee_rate_change_waits_for_timelock() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_rate(env.clone(), 100).unwrap()
+    });
+
+    let pending = env.as_contract(&contract_id, || {
+        EscrowContract::get_pending_change(env.clone(), change_id).unwrap()
+    });
+    assert_eq!(pending.change, ParamChange::FeeRate(100));
+    assert_eq!(
+        pending.executable_at,
+        env.ledger().sequence() + DEFAULT_TIMELOCK_DELAY
+    );
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id)
+    });
+    assert_eq!(result, Err(Error::TimelockNotElapsed));
+
+    env.ledger().set_sequence_number(pending.executable_at);
+    env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id).unwrap();
+    });
+
+    let fee_rate: u32 = env.as_contract(&contract_id, || {
+        env.storage().instance().get(&DataKey::FeeRate).unwrap()
+    });
+    assert_eq!(fee_rate, 100);
+
+    // An executed change cannot be replayed
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id)
+    });
+    assert_eq!(result, Err(Error::ChangeNotFound));
+}
+
+#[test]
+fn test_cancel_fee_treasury_change() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, new_treasury) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_treasury(env.clone(), new_treasury.clone()).unwrap()
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_change(env.clone(), change_id).unwrap();
+    });
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + DEFAULT_TIMELOCK_DELAY);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id)
+    });
+    assert_eq!(result, Err(Error::ChangeNotFound));
+
+    let stored_treasury: Address = env.as_contract(&contract_id, || {
+        env.storage().instance().get(&DataKey::FeeTreasury).unwrap()
+    });
+    assert_eq!(stored_treasury, treasury);
+}
+
+#[test]
+fn test_timelock_delay_change() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_timelock_delay(env.clone(), 10).unwrap()
+    });
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + DEFAULT_TIMELOCK_DELAY);
+    env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id).unwrap();
+    });
+
+    // Later proposals use the new delay
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_rate(env.clone(), 75).unwrap()
+    });
+    let pending = env.as_contract(&contract_id, || {
+        EscrowContract::get_pending_change(env.clone(), change_id).unwrap()
+    });
+    assert_eq!(pending.executable_at, env.ledger().sequence() + 10);
+}
+
+#[test]
+fn test_timelock_delay_above_max_rejected() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let (too_long, max) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::propose_timelock_delay(env.clone(), MAX_TIMELOCK_DELAY + 1),
+            EscrowContract::propose_timelock_delay(env.clone(), MAX_TIMELOCK_DELAY),
+        )
+    });
+    assert_eq!(too_long, Err(Error::InvalidTimelockDelay));
+    assert!(max.is_ok());
+}
+
+#[test]
+fn test_fee_tier_change_waits_for_timelock() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let token = Address::generate(&env);
+    let tiers = Vec::from_array(
+        &env,
+        [FeeTier {
+            min_amount: 0,
+            fee_rate: 1000,
+        }],
+    );
+
+    env.mock_all_auths();
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_tiers(env.clone(), token.clone(), tiers.clone()).unwrap()
+    });
+
+    let pending = env.as_contract(&contract_id, || {
+        EscrowContract::get_pending_change(env.clone(), change_id).unwrap()
+    });
+    assert_eq!(
+        pending.change,
+        ParamChange::FeeTiers(token.clone(), tiers.clone())
+    );
+
+    // The tier does not apply until the change is executed
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id)
+    });
+    assert_eq!(result, Err(Error::TimelockNotElapsed));
+    let fee_rate = env.as_contract(&contract_id, || {
+        EscrowContract::effective_fee_rate(&env, &token, 1000).unwrap()
+    });
+    assert_eq!(fee_rate, 50);
+
+    env.ledger().set_sequence_number(pending.executable_at);
+    env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id).unwrap();
+    });
+    let (stored, fee_rate) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_fee_tiers(env.clone(), token.clone()),
+            EscrowContract::effective_fee_rate(&env, &token, 1000).unwrap(),
+        )
+    });
+    assert_eq!(stored, tiers);
+    assert_eq!(fee_rate, 1000);
+
+    // Cancelling a queued tier change leaves the current tiers in place
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_tiers(env.clone(), token.clone(), Vec::new(&env)).unwrap()
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_change(env.clone(), change_id).unwrap();
+    });
+    let stored = env.as_contract(&contract_id, || {
+        EscrowContract::get_fee_tiers(env.clone(), token.clone())
+    });
+    assert_eq!(stored, tiers);
+}
+
+#[test]
+#[should_panic]
+fn test_non_admin_cannot_propose_fee_rate() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_rate(env.clone(), 100).unwrap();
+    });
+}
+
+#[test]
+fn test_invalid_fee_rate() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_rate(env.clone(), 1500)
+    });
+    assert_eq!(result, Err(Error::InvalidFeeRate));
+}
+
+#[test]
+fn test_admin_handover() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, new_admin) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::propose_admin(env.clone(), new_admin.clone()).unwrap();
+    });
+
+    // The current admin stays in charge until the proposal is accepted
+    let (stored_admin, pending_admin) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_admin(env.clone()).unwrap(),
+            EscrowContract::get_pending_admin(env.clone()),
+        )
+    });
+    assert_eq!(stored_admin, admin);
+    assert_eq!(pending_admin, Some(new_admin.clone()));
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_admin(env.clone()).unwrap();
+    });
+
+    let (stored_admin, pending_admin) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_admin(env.clone()).unwrap(),
+            EscrowContract::get_pending_admin(env.clone()),
+        )
+    });
+    assert_eq!(stored_admin, new_admin);
+    assert_eq!(pending_admin, None);
+}
+
+#[test]
+fn test_cancel_admin_proposal() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, new_admin) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::propose_admin(env.clone(), new_admin.clone()).unwrap();
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_admin_proposal(env.clone()).unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || EscrowContract::accept_admin(env.clone()));
+    assert_eq!(result, Err(Error::NoPendingAdmin));
+
+    let stored_admin = env.as_contract(&contract_id, || {
+        EscrowContract::get_admin(env.clone()).unwrap()
+    });
+    assert_eq!(stored_admin, admin);
+}
+
+#[test]
+#[should_panic]
+fn test_only_proposed_admin_can_accept() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, new_admin) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::propose_admin(env.clone(), new_admin.clone()).unwrap();
+    });
+
+    // Without a signature from the proposed admin the handover cannot complete
+    env.set_auths(&[]);
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_admin(env.clone()).unwrap();
+    });
+}
+
+#[test]
+fn test_pause_unpause() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::pause(env.clone()).unwrap();
+    });
+    let paused = env.as_contract(&contract_id, || EscrowContract::is_paused(env.clone()));
+    assert!(paused);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::unpause(env.clone()).unwrap();
+    });
+    let paused = env.as_contract(&contract_id, || EscrowContract::is_paused(env.clone()));
+    assert!(!paused);
+}
+
+#[test]
+fn test_is_paused() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let paused = env.as_contract(&contract_id, || EscrowContract::is_paused(env.clone()));
+    assert!(!paused);
+}
+
+#[test]
+fn test_get_admin() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || EscrowContract::get_admin(env.clone()));
+    assert_eq!(result, Ok(admin));
+}
+
+#[test]
+fn test_accept_order_not_found() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+    let buyer = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), 999, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::OrderNotFound));
+}
+
+#[test]
+fn test_accept_order_when_paused() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+    let buyer = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::pause(env.clone()).unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), 1, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::ContractPaused));
+}
+#[test]
+fn test_accept_order_invalid_status_locked() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create an order with Locked status
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Locked,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_accept_order_invalid_status_completed() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create an order with Completed status
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Completed,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_accept_order_expired() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let order_id = 1u64;
+
+    // Set timestamp to avoid overflow
+    env.ledger().set_timestamp(1000);
+
+    // Create an order that expires in the past
+    let expired_time = env.ledger().timestamp() - 1;
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        expired_time,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::OrderExpired));
+}
+
+#[test]
+fn test_accept_order_cannot_accept_own() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create an open order
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    // Seller tries to accept their own order
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, seller.clone())
+    });
+
+    assert_eq!(result, Err(Error::CannotAcceptOwnOrder));
+}
+
+#[test]
+fn test_accept_order_with_disputed_status() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create an order with Disputed status
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Disputed,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_accept_order_with_cancelled_status() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create an order with Cancelled status
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Cancelled,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_create_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let order_id = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+        .unwrap()
+    });
+    assert_eq!(order_id, 1);
+
+    let (order, order_count, seller_orders) = env.as_contract(&contract_id, || {
+        let order: Order = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap();
+        let order_count: u64 = env.storage().instance().get(&DataKey::OrderCount).unwrap();
+        let seller_orders: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserOrders(seller.clone()))
+            .unwrap();
+        (order, order_count, seller_orders)
+    });
+
+    let expected = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        expires_at,
+    );
+    assert_eq!(order, expected);
+    assert_eq!(order_count, 1);
+    assert_eq!(seller_orders, Vec::from_array(&env, [order_id]));
+}
+
+#[test]
+fn test_create_order_increments_id() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let create = || {
+        env.as_contract(&contract_id, || {
+            EscrowContract::create_order(
+                env.clone(),
+                seller.clone(),
+                token.clone(),
+                1000,
+                Symbol::new(&env, "NGN"),
+                1_500_000,
+                1500,
+                expires_at,
+                1800,
+                String::from_str(&env, "Bank Transfer"),
+            )
+            .unwrap()
+        })
+    };
+    assert_eq!(create(), 1);
+    assert_eq!(create(), 2);
+
+    let seller_orders: Vec<u64> = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserOrders(seller.clone()))
+            .unwrap()
+    });
+    assert_eq!(seller_orders, Vec::from_array(&env, [1, 2]));
+}
+
+#[test]
+fn test_create_order_invalid_amount() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            0,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidAmount));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            -100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidAmount));
+}
+
+#[test]
+fn test_create_order_invalid_expiration() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    env.ledger().set_timestamp(1000);
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            1000,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidExpiration));
+}
+
+#[test]
+fn test_create_order_when_paused() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::pause(env.clone()).unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::ContractPaused));
+}
+
+#[test]
+fn test_create_and_accept_order_locks_funds() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+        .unwrap()
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 0);
+    assert_eq!(token_client.balance(&contract_id), 1000);
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Locked);
+    assert_eq!(order.buyer, Some(buyer));
+}
+
+#[test]
+fn test_mark_payment_sent() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+    });
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::PaymentSent);
+}
+
+#[test]
+fn test_mark_payment_sent_invalid_status() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // Create a completed order that already has a buyer
+    let mut order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Completed,
+        env.ledger().timestamp() + 3600,
+    );
+    order.buyer = Some(buyer.clone());
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_mark_payment_sent_open_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    // An open order has no buyer to confirm the payment
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+#[should_panic]
+fn test_mark_payment_sent_requires_buyer_auth() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    let mut order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Locked,
+        env.ledger().timestamp() + 3600,
+    );
+    order.buyer = Some(buyer.clone());
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+    });
+}
+
+#[test]
+fn test_release_pays_buyer_and_treasury() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+        EscrowContract::release(env.clone(), order_id).unwrap();
+    });
+
+    // 50 basis points of 1000 is 5
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&buyer), 995);
+    assert_eq!(token_client.balance(&treasury), 5);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Completed);
+}
+
+#[test]
+fn test_release_twice_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id).unwrap();
+    });
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_release_open_order_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_release_when_paused() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::pause(env.clone()).unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::ContractPaused));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::ContractPaused));
+}
+
+#[test]
+fn test_raise_dispute_by_buyer() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.ledger().set_timestamp(500);
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+        EscrowContract::raise_dispute(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+
+    let (order, dispute) = env.as_contract(&contract_id, || {
+        let order: Order = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap();
+        let dispute = EscrowContract::get_dispute(env.clone(), order_id).unwrap();
+        (order, dispute)
+    });
+    assert_eq!(order.status, OrderStatus::Disputed);
+    assert_eq!(dispute.raised_by, buyer);
+    assert_eq!(dispute.previous_status, OrderStatus::PaymentSent);
+    assert_eq!(dispute.raised_at, 500);
+    assert_eq!(dispute.outcome, DisputeOutcome::Pending);
+
+    // A disputed order can no longer be released by the seller
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_raise_dispute_by_outsider_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, outsider) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, outsider.clone())
+    });
+    assert_eq!(result, Err(Error::Unauthorized));
+}
+
+#[test]
+fn test_raise_dispute_on_open_order_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, seller.clone())
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_resolve_dispute_splits_funds() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, seller.clone()).unwrap();
+        EscrowContract::resolve_dispute(env.clone(), order_id, 2_500).unwrap();
+    });
+
+    // Fee of 5 comes off the top, the remaining 995 is split 25/75
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&buyer), 248);
+    assert_eq!(token_client.balance(&seller), 747);
+    assert_eq!(token_client.balance(&treasury), 5);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let (order, dispute) = env.as_contract(&contract_id, || {
+        let order: Order = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap();
+        let dispute = EscrowContract::get_dispute(env.clone(), order_id).unwrap();
+        (order, dispute)
+    });
+    assert_eq!(order.status, OrderStatus::Resolved);
+
+    let resolution = match dispute.outcome {
+        DisputeOutcome::Resolved(resolution) => resolution,
+        DisputeOutcome::Pending => panic!("dispute should be resolved"),
+    };
+    assert_eq!(resolution.resolver, resolver);
+    assert_eq!(resolution.buyer_share_bps, 2_500);
+    assert_eq!(resolution.buyer_amount, 248);
+    assert_eq!(resolution.seller_amount, 747);
+    assert_eq!(resolution.fee, 5);
+}
+
+#[test]
+fn test_resolve_dispute_invalid_share() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), 1, 10_001)
+    });
+    assert_eq!(result, Err(Error::InvalidDisputeShare));
+}
+
+#[test]
+fn test_resolve_undisputed_order_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), order_id, 5_000)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+#[should_panic]
+fn test_resolve_dispute_requires_resolver_auth() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), 1, 5_000).unwrap();
+    });
+}
+
+#[test]
+fn test_cancel_open_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), order_id).unwrap();
+    });
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Cancelled);
+
+    // A cancelled order can no longer be accepted
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_cancel_locked_order_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+#[should_panic]
+fn test_cancel_order_requires_seller_auth() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let order_id = 1u64;
+
+    let order = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        env.ledger().timestamp() + 3600,
+    );
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Order(order_id), &order);
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), order_id).unwrap();
+    });
+}
+
+#[test]
+fn test_create_order_invalid_payment_window() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            0,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidPaymentWindow));
+}
+
+#[test]
+fn test_reclaim_expired_returns_funds() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.payment_deadline, 2800);
+
+    env.ledger().set_timestamp(2801);
+    env.as_contract(&contract_id, || {
+        EscrowContract::reclaim_expired(env.clone(), order_id).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 1000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    let order: Order = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Order(order_id))
+            .unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Cancelled);
+}
+
+#[test]
+fn test_reclaim_within_payment_window_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.ledger().set_timestamp(2800);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::reclaim_expired(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::PaymentWindowActive));
+}
+
+#[test]
+fn test_reclaim_after_payment_sent_fails() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+    });
+
+    env.ledger().set_timestamp(5000);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::reclaim_expired(env.clone(), order_id)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+}
+
+#[test]
+fn test_get_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    let expected = create_mock_order(
+        &env,
+        &seller,
+        &token,
+        order_id,
+        OrderStatus::Open,
+        expires_at,
+    );
+    assert_eq!(order, expected);
+
+    let result = env.as_contract(&contract_id, || EscrowContract::get_order(env.clone(), 999));
+    assert_eq!(result, Err(Error::OrderNotFound));
+}
+
+#[test]
+fn test_get_user_orders_paginates() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let other = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    for _ in 0..3 {
+        create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+    }
+    create_open_order(&env, &contract_id, &other, &token, "USD", expires_at);
+
+    let (first_page, second_page, past_end, unknown) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_user_orders(env.clone(), seller.clone(), 0, 2),
+            EscrowContract::get_user_orders(env.clone(), seller.clone(), 2, 2),
+            EscrowContract::get_user_orders(env.clone(), seller.clone(), 5, 2),
+            EscrowContract::get_user_orders(env.clone(), Address::generate(&env), 0, 10),
+        )
+    });
+
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get_unchecked(0).id, 1);
+    assert_eq!(first_page.get_unchecked(1).id, 2);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get_unchecked(0).id, 3);
+    assert_eq!(past_end.len(), 0);
+    assert_eq!(unknown.len(), 0);
+}
+
+#[test]
+fn test_get_open_orders_filters_by_token_and_currency() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let other_token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    allow_token(&env, &contract_id, &other_token);
+    create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+    create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+    create_open_order(&env, &contract_id, &seller, &other_token, "NGN", expires_at);
+    create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "NGN", None, 10),
+        Vec::from_array(&env, [1, 4])
+    );
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "USD", None, 10),
+        Vec::from_array(&env, [2])
+    );
+    assert_eq!(
+        open_order_ids(
+            &env,
+            &contract_id,
+            OrderSide::Sell,
+            &other_token,
+            "NGN",
+            None,
+            10
+        ),
+        Vec::from_array(&env, [3])
+    );
+    assert_eq!(
+        open_order_ids(
+            &env,
+            &contract_id,
+            OrderSide::Sell,
+            &token,
+            "NGN",
+            Some(1),
+            10
+        ),
+        Vec::from_array(&env, [4])
+    );
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "NGN", None, 1),
+        Vec::from_array(&env, [1])
+    );
+}
+
+#[test]
+fn test_open_orders_index_tracks_state_changes() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let accepted = create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+    let cancelled = create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+    let remaining = create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), accepted, buyer.clone()).unwrap();
+        EscrowContract::cancel_order(env.clone(), cancelled).unwrap();
+    });
+
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "NGN", None, 10),
+        Vec::from_array(&env, [remaining])
+    );
+    let (list, link, unlinked) = env.as_contract(&contract_id, || {
+        let list: OpenOrderList = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OpenOrderList(
+                OrderSide::Sell,
+                token.clone(),
+                Symbol::new(&env, "NGN"),
+            ))
+            .unwrap();
+        let link: OpenOrderLink = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OpenOrderLink(remaining))
+            .unwrap();
+        let unlinked = !env
+            .storage()
+            .persistent()
+            .has(&DataKey::OpenOrderLink(accepted))
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::OpenOrderLink(cancelled));
+        (list, link, unlinked)
+    });
+    assert_eq!(
+        list,
+        OpenOrderList {
+            head: Some(remaining),
+            tail: Some(remaining),
+        }
+    );
+    assert_eq!(
+        link,
+        OpenOrderLink {
+            prev: None,
+            next: None,
+        }
+    );
+    assert!(unlinked);
+}
+
+#[test]
+fn test_open_orders_paginate_by_cursor() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    for _ in 0..5 {
+        create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+    }
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), 3).unwrap();
+    });
+
+    let (first, second) = env.as_contract(&contract_id, || {
+        let page = |cursor| {
+            EscrowContract::get_open_orders(
+                env.clone(),
+                OrderSide::Sell,
+                token.clone(),
+                Symbol::new(&env, "NGN"),
+                cursor,
+                2,
+            )
+            .unwrap()
+        };
+        let first = page(None);
+        let second = page(first.next_cursor);
+        (first, second)
+    });
+    assert_eq!(first.orders.len(), 2);
+    assert_eq!(first.next_cursor, Some(2));
+    assert_eq!(second.orders.get_unchecked(0).id, 4);
+    assert_eq!(second.orders.get_unchecked(1).id, 5);
+    assert_eq!(second.next_cursor, None);
+
+    // A cursor that has left the list cannot be resumed from
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::get_open_orders(
+            env.clone(),
+            OrderSide::Sell,
+            token.clone(),
+            Symbol::new(&env, "NGN"),
+            Some(3),
+            2,
+        )
+    });
+    assert_eq!(result, Err(Error::OrderNotFound));
+}
+
+#[test]
+fn test_open_orders_capped_per_maker() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    for _ in 0..MAX_OPEN_ORDERS_PER_MAKER {
+        create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+    }
+
+    let create = || {
+        env.as_contract(&contract_id, || {
+            EscrowContract::create_order(
+                env.clone(),
+                seller.clone(),
+                token.clone(),
+                1000,
+                Symbol::new(&env, "USD"),
+                100,
+                10,
+                expires_at,
+                1800,
+                String::from_str(&env, "Bank Transfer"),
+            )
+        })
+    };
+    assert_eq!(create(), Err(Error::TooManyOpenOrders));
+
+    // Closing a listing frees a slot
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), 1).unwrap();
+    });
+    assert!(create().is_ok());
+}
+
+#[test]
+fn test_expire_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let short_lived = create_open_order(&env, &contract_id, &seller, &token, "NGN", 1500);
+    let long_lived = create_open_order(&env, &contract_id, &seller, &token, "NGN", 5000);
+
+    // Expiring before the deadline is rejected
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::expire_order(env.clone(), short_lived)
+    });
+    assert_eq!(result, Err(Error::InvalidExpiration));
+
+    // Expired orders drop out of listings even before they are closed
+    env.ledger().set_timestamp(2000);
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "NGN", None, 10),
+        Vec::from_array(&env, [long_lived])
+    );
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::expire_order(env.clone(), short_lived).unwrap();
+    });
+
+    let (order, list) = env.as_contract(&contract_id, || {
+        let order = EscrowContract::get_order(env.clone(), short_lived).unwrap();
+        let list: OpenOrderList = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OpenOrderList(
+                OrderSide::Sell,
+                token.clone(),
+                Symbol::new(&env, "NGN"),
+            ))
+            .unwrap();
+        (order, list)
+    });
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert_eq!(
+        list,
+        OpenOrderList {
+            head: Some(long_lived),
+            tail: Some(long_lived),
+        }
+    );
+
+    // Expiry is permissionless, so it does not count as a cancellation by the seller
+    let reputation = env.as_contract(&contract_id, || {
+        EscrowContract::get_reputation(env.clone(), seller.clone())
+    });
+    assert_eq!(reputation.cancelled_orders, 0);
+}
+
+#[test]
+fn test_accept_partial_creates_child_trade() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let parent_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    let child_id = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), parent_id, buyer.clone(), 300).unwrap()
+    });
+    assert_eq!(child_id, 2);
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 700);
+    assert_eq!(token_client.balance(&contract_id), 300);
+
+    let (parent, child, children) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_order(env.clone(), parent_id).unwrap(),
+            EscrowContract::get_order(env.clone(), child_id).unwrap(),
+            EscrowContract::get_child_orders(env.clone(), parent_id),
+        )
+    });
+    assert_eq!(parent.status, OrderStatus::Open);
+    assert_eq!(parent.amount, 700);
+    assert_eq!(parent.fiat_amount, 70);
+    assert_eq!(child.status, OrderStatus::Locked);
+    assert_eq!(child.amount, 300);
+    assert_eq!(child.fiat_amount, 30);
+    assert_eq!(child.buyer, Some(buyer.clone()));
+    assert_eq!(child.parent_id, Some(parent_id));
+    assert_eq!(children, Vec::from_array(&env, [child_id]));
+
+    // The parent stays listed with the remaining amount
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "USD", None, 10),
+        Vec::from_array(&env, [parent_id])
+    );
+
+    let buyer_orders = env.as_contract(&contract_id, || {
+        EscrowContract::get_user_orders(env.clone(), buyer.clone(), 0, 10)
+    });
+    assert_eq!(buyer_orders.len(), 1);
+    assert_eq!(buyer_orders.get_unchecked(0).parent_id, Some(parent_id));
+}
+
+#[test]
+fn test_partial_fills_settle_independently() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let first_buyer = Address::generate(&env);
+    let second_buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let parent_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    let first_child = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), parent_id, first_buyer.clone(), 400).unwrap()
+    });
+    // The last buyer takes whatever is left on the parent
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), parent_id, second_buyer.clone()).unwrap();
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), first_child).unwrap();
+        EscrowContract::release(env.clone(), first_child).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&first_buyer), 398);
+    assert_eq!(token_client.balance(&treasury), 2);
+    assert_eq!(token_client.balance(&contract_id), 600);
+
+    let parent = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), parent_id).unwrap()
+    });
+    assert_eq!(parent.status, OrderStatus::Locked);
+    assert_eq!(parent.amount, 600);
+    assert_eq!(parent.buyer, Some(second_buyer));
+}
+
+#[test]
+fn test_accept_partial_invalid_amount() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let parent_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    for amount in [0, 1000, 1500, 5] {
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::accept_partial(env.clone(), parent_id, buyer.clone(), amount)
+        });
+        assert_eq!(result, Err(Error::InvalidAmount));
     }
+}
+
+#[test]
+fn test_accept_partial_cannot_accept_own() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let parent_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), parent_id, seller.clone(), 100)
+    });
+    assert_eq!(result, Err(Error::CannotAcceptOwnOrder));
+}
+
+#[test]
+fn test_create_buy_order() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-    fn create_addresses(env: &Env) -> (Address, Address, Address, Address) {
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_buy_order(&env, &contract_id, &buyer, &token, expires_at);
+
+    let (order, buyer_orders) = env.as_contract(&contract_id, || {
         (
-            Address::generate(env),
-            Address::generate(env),
-            Address::generate(env),
-            Address::generate(env),
+            EscrowContract::get_order(env.clone(), order_id).unwrap(),
+            EscrowContract::get_user_orders(env.clone(), buyer.clone(), 0, 10),
         )
-    }
+    });
+    assert_eq!(order.side, OrderSide::Buy);
+    assert_eq!(order.buyer, Some(buyer.clone()));
+    assert_eq!(order.seller, None);
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(buyer_orders.len(), 1);
 
-    #[test]
-    fn test_initialize() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    // Buy orders are listed separately from sell orders
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Buy, &token, "NGN", None, 10),
+        Vec::from_array(&env, [order_id])
+    );
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Sell, &token, "NGN", None, 10),
+        Vec::new(&env)
+    );
+}
 
-        let result = EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone());
-        assert!(result.is_ok());
+#[test]
+fn test_buy_order_full_lifecycle() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-        let stored_admin = env.storage().instance().get(&DataKey::Admin).unwrap();
-        assert_eq!(stored_admin, admin);
-        assert!(!EscrowContract::is_paused(&env));
-    }
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
 
-    #[test]
-    fn test_prevent_double_initialization() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
-        let result = EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone());
-        assert_eq!(result, Err(Error::AlreadyInitialized));
-    }
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_buy_order(&env, &contract_id, &buyer, &token, expires_at);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_buy_order(env.clone(), order_id, seller.clone()).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 0);
+    assert_eq!(token_client.balance(&contract_id), 1000);
+
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Locked);
+    assert_eq!(order.seller, Some(seller.clone()));
+    assert_eq!(
+        open_order_ids(&env, &contract_id, OrderSide::Buy, &token, "NGN", None, 10),
+        Vec::new(&env)
+    );
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+        EscrowContract::release(env.clone(), order_id).unwrap();
+    });
+
+    assert_eq!(token_client.balance(&buyer), 995);
+    assert_eq!(token_client.balance(&treasury), 5);
+}
+
+#[test]
+fn test_buy_order_dispute() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_buy_order(&env, &contract_id, &buyer, &token, expires_at);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_buy_order(env.clone(), order_id, seller.clone()).unwrap();
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, seller.clone()).unwrap();
+        EscrowContract::resolve_dispute(env.clone(), order_id, 0).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 995);
+    assert_eq!(token_client.balance(&buyer), 0);
+}
+
+#[test]
+fn test_accept_order_wrong_side() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let buy_order = create_open_buy_order(&env, &contract_id, &buyer, &token, expires_at);
+    let sell_order = create_open_order(&env, &contract_id, &seller, &token, "NGN", expires_at);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), buy_order, seller.clone())
+    });
+    assert_eq!(result, Err(Error::InvalidOrderSide));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), buy_order, seller.clone(), 100)
+    });
+    assert_eq!(result, Err(Error::InvalidOrderSide));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_buy_order(env.clone(), sell_order, buyer.clone())
+    });
+    assert_eq!(result, Err(Error::InvalidOrderSide));
+}
+
+#[test]
+fn test_accept_buy_order_cannot_accept_own() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_buy_order(&env, &contract_id, &buyer, &token, expires_at);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_buy_order(env.clone(), order_id, buyer.clone())
+    });
+    assert_eq!(result, Err(Error::CannotAcceptOwnOrder));
+
+    // The buyer who posted the order can cancel it
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), order_id).unwrap();
+    });
+}
+
+#[test]
+fn test_fee_rate_is_snapshotted_at_acceptance() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
 
-    #[test]
-    fn test_set_fee_rate() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    // Raising the global rate does not affect the order already in flight
+    let change_id = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_rate(env.clone(), 1000).unwrap()
+    });
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + DEFAULT_TIMELOCK_DELAY);
+    env.as_contract(&contract_id, || {
+        EscrowContract::execute_change(env.clone(), change_id).unwrap();
+    });
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    let (order, quote) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_order(env.clone(), order_id).unwrap(),
+            EscrowContract::quote_fee(env.clone(), order_id).unwrap(),
+        )
+    });
+    assert_eq!(order.fee_rate, 50);
+    assert_eq!(quote, 5);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), order_id).unwrap();
+    });
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&buyer), 995);
+    assert_eq!(token_client.balance(&treasury), 5);
+}
+
+#[test]
+fn test_fee_tiers_by_token_and_size() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let stablecoin = Address::generate(&env);
+    let other_token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &stablecoin);
+    allow_token(&env, &contract_id, &other_token);
+    set_fee_tiers(
+        &env,
+        &contract_id,
+        &stablecoin,
+        Vec::from_array(
+            &env,
+            [
+                FeeTier {
+                    min_amount: 0,
+                    fee_rate: 30,
+                },
+                FeeTier {
+                    min_amount: 1000,
+                    fee_rate: 10,
+                },
+            ],
+        ),
+    );
+
+    let large_order =
+        create_open_order(&env, &contract_id, &seller, &stablecoin, "USD", expires_at);
+    let other_order =
+        create_open_order(&env, &contract_id, &seller, &other_token, "USD", expires_at);
+
+    let (large_quote, other_quote) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::quote_fee(env.clone(), large_order).unwrap(),
+            EscrowContract::quote_fee(env.clone(), other_order).unwrap(),
+        )
+    });
+    // 10 basis points on the large stablecoin trade, the global 50 elsewhere
+    assert_eq!(large_quote, 1);
+    assert_eq!(other_quote, 5);
+
+    let small_rate = env.as_contract(&contract_id, || {
+        EscrowContract::effective_fee_rate(&env, &stablecoin, 999).unwrap()
+    });
+    assert_eq!(small_rate, 30);
+
+    // Clearing the tiers falls back to the global rate
+    set_fee_tiers(&env, &contract_id, &stablecoin, Vec::new(&env));
+    let large_quote = env.as_contract(&contract_id, || {
+        EscrowContract::quote_fee(env.clone(), large_order).unwrap()
+    });
+    assert_eq!(large_quote, 5);
+}
+
+#[test]
+fn test_partial_fill_uses_tier_for_fill_size() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    set_fee_tiers(
+        &env,
+        &contract_id,
+        &token,
+        Vec::from_array(
+            &env,
+            [FeeTier {
+                min_amount: 500,
+                fee_rate: 20,
+            }],
+        ),
+    );
+
+    let parent_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+    let child_id = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), parent_id, buyer.clone(), 300).unwrap()
+    });
+
+    let child = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), child_id).unwrap()
+    });
+    assert_eq!(child.fee_rate, 50);
+}
+
+#[test]
+fn test_propose_fee_tiers_validation() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let token = Address::generate(&env);
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_tiers(
+            env.clone(),
+            token.clone(),
+            Vec::from_array(
+                &env,
+                [FeeTier {
+                    min_amount: 0,
+                    fee_rate: 1500,
+                }],
+            ),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidFeeRate));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::propose_fee_tiers(
+            env.clone(),
+            token.clone(),
+            Vec::from_array(
+                &env,
+                [
+                    FeeTier {
+                        min_amount: 1000,
+                        fee_rate: 10,
+                    },
+                    FeeTier {
+                        min_amount: 1000,
+                        fee_rate: 5,
+                    },
+                ],
+            ),
+        )
+    });
+    assert_eq!(result, Err(Error::InvalidFeeTier));
+
+    let tiers = env.as_contract(&contract_id, || {
+        EscrowContract::get_fee_tiers(env.clone(), token.clone())
+    });
+    assert!(tiers.is_empty());
+}
+
+#[test]
+fn test_create_order_token_not_allowed() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-        env.mock_auths(&[admin.clone()]);
-        let result = EscrowContract::set_fee_rate(&env, 100);
-        assert!(result.is_ok());
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::create_order(
+            env.clone(),
+            seller.clone(),
+            token.clone(),
+            1000,
+            Symbol::new(&env, "USD"),
+            100,
+            10,
+            expires_at,
+            1800,
+            String::from_str(&env, "Bank Transfer"),
+        )
+    });
+    assert_eq!(result, Err(Error::TokenNotAllowed));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::get_token_limits(env.clone(), token.clone())
+    });
+    assert_eq!(result, Err(Error::TokenNotAllowed));
+}
+
+#[test]
+fn test_create_order_amount_out_of_range() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::allow_token(env.clone(), token.clone(), 100, 500).unwrap();
+    });
+
+    let limits = env.as_contract(&contract_id, || {
+        EscrowContract::get_token_limits(env.clone(), token.clone()).unwrap()
+    });
+    assert_eq!(
+        limits,
+        TokenLimits {
+            min_amount: 100,
+            max_amount: 500,
+        }
+    );
+
+    for amount in [99, 501] {
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::create_order(
+                env.clone(),
+                seller.clone(),
+                token.clone(),
+                amount,
+                Symbol::new(&env, "USD"),
+                100,
+                10,
+                expires_at,
+                1800,
+                String::from_str(&env, "Bank Transfer"),
+            )
+        });
+        assert_eq!(result, Err(Error::AmountOutOfRange));
     }
+}
+
+#[test]
+fn test_accept_order_token_removed_from_allowlist() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::remove_token(env.clone(), token.clone()).unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+    assert_eq!(result, Err(Error::TokenNotAllowed));
+}
+
+#[test]
+fn test_accept_partial_below_token_minimum() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::allow_token(env.clone(), token.clone(), 200, 5000).unwrap();
+    });
+    let order_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), order_id, buyer.clone(), 100)
+    });
+    assert_eq!(result, Err(Error::AmountOutOfRange));
+}
+
+#[test]
+fn test_accept_partial_cannot_leave_dust() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let expires_at = env.ledger().timestamp() + 3600;
+
+    env.mock_all_auths_allowing_non_root_auth();
+    env.as_contract(&contract_id, || {
+        EscrowContract::allow_token(env.clone(), token.clone(), 200, 5000).unwrap();
+    });
+    let order_id = create_open_order(&env, &contract_id, &seller, &token, "USD", expires_at);
+
+    // 850 would leave 150 on the parent, below the 200 minimum
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), order_id, buyer.clone(), 850)
+    });
+    assert_eq!(result, Err(Error::AmountOutOfRange));
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_partial(env.clone(), order_id, buyer.clone(), 800).unwrap();
+    });
+    let parent = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    assert_eq!(parent.amount, 200);
+    assert_eq!(parent.fiat_amount, 20);
+}
 
-    #[test]
-    fn test_non_admin_cannot_set_fee_rate() {
-        let env = create_env();
-        let (admin, treasury, resolver, non_admin) = create_addresses(&env);
+#[test]
+fn test_allow_token_invalid_limits() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let token = Address::generate(&env);
 
-        env.mock_auths(&[non_admin.clone()]);
-        let result = EscrowContract::set_fee_rate(&env, 100);
-        assert_eq!(result, Err(Error::Unauthorized));
+    env.mock_all_auths();
+    for (min_amount, max_amount) in [(0, 100), (500, 100)] {
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::allow_token(env.clone(), token.clone(), min_amount, max_amount)
+        });
+        assert_eq!(result, Err(Error::InvalidAmount));
     }
+}
+
+#[test]
+#[should_panic]
+fn test_non_admin_cannot_allow_token() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let token = Address::generate(&env);
+    env.as_contract(&contract_id, || {
+        EscrowContract::allow_token(env.clone(), token.clone(), 1, 1000).unwrap();
+    });
+}
+
+#[test]
+fn test_order_ttl_extended_on_touch() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    let (order_ttl, user_orders_ttl) = env.as_contract(&contract_id, || {
+        (
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Order(order_id)),
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::UserOrders(buyer.clone())),
+        )
+    });
+    assert_eq!(order_ttl, DEFAULT_TTL_EXTEND_TO);
+    assert_eq!(user_orders_ttl, DEFAULT_TTL_EXTEND_TO);
+}
+
+#[test]
+fn test_extend_order_ttl() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+
+    // Let the entries age past the extension threshold
+    let start = env.ledger().sequence();
+    env.ledger()
+        .set_sequence_number(start + DEFAULT_TTL_EXTEND_TO - DEFAULT_TTL_THRESHOLD + 1);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::extend_order_ttl(env.clone(), order_id).unwrap();
+    });
+
+    let (order_ttl, dispute_ttl) = env.as_contract(&contract_id, || {
+        (
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Order(order_id)),
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Dispute(order_id)),
+        )
+    });
+    assert_eq!(order_ttl, DEFAULT_TTL_EXTEND_TO);
+    assert_eq!(dispute_ttl, DEFAULT_TTL_EXTEND_TO);
 
-    #[test]
-    fn test_invalid_fee_rate() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::extend_order_ttl(env.clone(), 999)
+    });
+    assert_eq!(result, Err(Error::OrderNotFound));
+}
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+#[test]
+fn test_set_ttl_config() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-        env.mock_auths(&[admin.clone()]);
-        let result = EscrowContract::set_fee_rate(&env, 1500);
-        assert_eq!(result, Err(Error::InvalidFeeRate));
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    env.as_contract(&contract_id, || {
+        EscrowContract::set_ttl_config(env.clone(), 4500, 5000).unwrap();
+    });
+    let config = env.as_contract(&contract_id, || EscrowContract::get_ttl_config(env.clone()));
+    assert_eq!(
+        config,
+        TtlConfig {
+            threshold: 4500,
+            extend_to: 5000,
+        }
+    );
+
+    let seller = Address::generate(&env);
+    let token = Address::generate(&env);
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_order(
+        &env,
+        &contract_id,
+        &seller,
+        &token,
+        "USD",
+        env.ledger().timestamp() + 3600,
+    );
+    let order_ttl = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&DataKey::Order(order_id))
+    });
+    assert_eq!(order_ttl, 5000);
+
+    for (threshold, extend_to) in [(0, 5000), (5000, 5000), (4500, u32::MAX)] {
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::set_ttl_config(env.clone(), threshold, extend_to)
+        });
+        assert_eq!(result, Err(Error::InvalidTtlConfig));
     }
+}
+
+#[test]
+fn test_prune_user_orders() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 2000);
 
-    #[test]
-    fn test_set_admin() {
-        let env = create_env();
-        let (admin, treasury, resolver, new_admin) = create_addresses(&env);
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let completed = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    let in_flight = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), completed).unwrap();
+    });
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    // Nothing is old enough yet
+    let removed = env.as_contract(&contract_id, || {
+        EscrowContract::prune_user_orders(env.clone(), buyer.clone(), 30).unwrap()
+    });
+    assert_eq!(removed, 0);
 
-        env.mock_auths(&[admin.clone()]);
-        EscrowContract::set_admin(&env, new_admin.clone()).unwrap();
+    env.ledger().set_timestamp(1000 + 31 * DAY_IN_SECONDS);
+    let removed = env.as_contract(&contract_id, || {
+        EscrowContract::prune_user_orders(env.clone(), buyer.clone(), 30).unwrap()
+    });
+    assert_eq!(removed, 1);
 
-        let stored_admin = env.storage().instance().get(&DataKey::Admin).unwrap();
-        assert_eq!(stored_admin, new_admin);
+    let buyer_orders: Vec<u64> = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserOrders(buyer.clone()))
+            .unwrap()
+    });
+    assert_eq!(buyer_orders, Vec::from_array(&env, [in_flight]));
+
+    // The pruned order itself is still readable
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), completed).unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Completed);
+}
+
+#[test]
+fn test_prune_user_orders_counts_from_closing_time() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::mark_payment_sent(env.clone(), order_id).unwrap();
+        EscrowContract::raise_dispute(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+
+    // The dispute drags on well past the pruning period
+    let resolved_at = 1000 + 60 * DAY_IN_SECONDS;
+    env.ledger().set_timestamp(resolved_at);
+    env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), order_id, 10_000).unwrap();
+    });
+
+    env.ledger().set_timestamp(resolved_at + DAY_IN_SECONDS);
+    let removed = env.as_contract(&contract_id, || {
+        EscrowContract::prune_user_orders(env.clone(), buyer.clone(), 30).unwrap()
+    });
+    assert_eq!(removed, 0);
+
+    env.ledger()
+        .set_timestamp(resolved_at + 31 * DAY_IN_SECONDS);
+    let (removed, order) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::prune_user_orders(env.clone(), buyer.clone(), 30).unwrap(),
+            EscrowContract::get_order(env.clone(), order_id).unwrap(),
+        )
+    });
+    assert_eq!(removed, 1);
+    assert_eq!(order.closed_at, resolved_at);
+}
+
+#[test]
+fn test_non_admin_cannot_upgrade() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let (admin, treasury, resolver, attacker) = create_addresses(&env);
+
+    client.initialize(&admin, &50, &treasury, &resolver);
+    // A real uploaded hash, so only the missing admin signature can fail the call
+    let wasm_hash = env.deployer().upload_contract_wasm(escrow_bindings::WASM);
+
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "upgrade",
+                args: (wasm_hash.clone(),).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_upgrade(&wasm_hash);
+    assert_eq!(result, Err(Err(InvokeError::Abort)));
+    assert!(env.auths().is_empty());
+
+    client
+        .mock_auths(&[MockAuth {
+            address: &admin,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "upgrade",
+                args: (wasm_hash.clone(),).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .upgrade(&wasm_hash);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "upgrade"),
+                    (wasm_hash,).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_initialize_stores_version() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    env.mock_all_auths();
+    let (version, result) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::version(env.clone()),
+            EscrowContract::migrate(env.clone()),
+        )
+    });
+    assert_eq!(version, CONTRACT_VERSION);
+    assert_eq!(result, Err(Error::AlreadyMigrated));
+}
+
+#[test]
+fn test_upgrade_and_migrate_with_locked_orders() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 2000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let first = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    let second = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    // Enough open orders to need more than one migration batch
+    for _ in 0..MIGRATION_BATCH_SIZE {
+        create_open_order(
+            &env,
+            &contract_id,
+            &Address::generate(&env),
+            &token,
+            "USD",
+            env.ledger().timestamp() + 3600,
+        );
     }
 
-    #[test]
-    fn test_pause_unpause() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    // Rewind storage to what version 1 of the contract would have written
+    let usd = Symbol::new(&env, "USD");
+    env.as_contract(&contract_id, || {
+        let order_count: u64 = env.storage().instance().get(&DataKey::OrderCount).unwrap();
+        let mut open_ids = Vec::new(&env);
+        for order_id in 1..=order_count {
+            let order: Order = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Order(order_id))
+                .unwrap();
+            if order.status == OrderStatus::Open {
+                open_ids.push_back(order_id);
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::OpenOrderLink(order_id));
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::OpenOrderCount(order.seller.clone().unwrap()));
+            }
+            let legacy = OrderV1 {
+                id: order.id,
+                side: order.side,
+                seller: order.seller,
+                buyer: order.buyer,
+                token: order.token,
+                amount: order.amount,
+                fiat_currency: order.fiat_currency,
+                fiat_amount: order.fiat_amount,
+                rate: order.rate,
+                status: order.status,
+                created_at: order.created_at,
+                expires_at: order.expires_at,
+                payment_window: order.payment_window,
+                payment_deadline: order.payment_deadline,
+                payment_method: order.payment_method,
+                parent_id: order.parent_id,
+                fee_rate: order.fee_rate,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::Order(order_id), &legacy);
+        }
+        let legacy_key = DataKey::OpenOrders(OrderSide::Sell, token.clone(), usd.clone());
+        env.storage().persistent().remove(&DataKey::OpenOrderList(
+            OrderSide::Sell,
+            token.clone(),
+            usd.clone(),
+        ));
+        env.storage().persistent().set(&legacy_key, &open_ids);
+        env.storage().instance().set(&DataKey::Version, &1u32);
+    });
+
+    // Swap in the contract WASM at the same address; storage is left as it was and
+    // every call below runs the uploaded code
+    env.budget().reset_unlimited();
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let wasm_hash = env.deployer().upload_contract_wasm(escrow_bindings::WASM);
+    client.upgrade(&wasm_hash);
+
+    // Orders cannot be touched until they are back in the current layout
+    assert_eq!(client.try_release(&first), Err(Ok(Error::MigrationPending)));
+
+    assert!(!client.migrate());
+    assert!(client.migrate());
+
+    let first_order = client.get_order(&first);
+    let second_order = client.get_order(&second);
+    assert_eq!(client.version(), CONTRACT_VERSION);
+    assert_eq!(first_order.status, OrderStatus::Locked);
+    assert_eq!(second_order.status, OrderStatus::Locked);
+    assert_eq!(first_order.buyer, Some(buyer.clone()));
+
+    // Open orders moved from the legacy vector into the linked list, in order
+    let page = client.get_open_orders(&OrderSide::Sell, &token, &usd, &None, &100);
+    assert_eq!(page.orders.len(), MIGRATION_BATCH_SIZE as u32);
+    assert_eq!(page.orders.get_unchecked(0).id, second + 1);
+    assert_eq!(page.next_cursor, None);
+    let legacy_removed = env.as_contract(&contract_id, || {
+        !env.storage().persistent().has(&DataKey::OpenOrders(
+            OrderSide::Sell,
+            token.clone(),
+            usd.clone(),
+        ))
+    });
+    assert!(legacy_removed);
+
+    // Locked funds are still releasable after the upgrade
+    client.release(&first);
+    client.release(&second);
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&buyer), 1990);
+    assert_eq!(token_client.balance(&treasury), 10);
+}
 
-        env.mock_auths(&[admin.clone()]);
-        EscrowContract::pause(&env).unwrap();
-        assert!(EscrowContract::is_paused(&env));
+/// Order layout of the escrow contract before storage versioning
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PreVersioningOrder {
+    id: u64,
+    seller: Address,
+    buyer: Option<Address>,
+    token: Address,
+    amount: i128,
+    fiat_currency: Symbol,
+    fiat_amount: i128,
+    rate: i128,
+    status: OrderStatus,
+    created_at: u64,
+    expires_at: u64,
+    payment_method: String,
+}
 
-        env.mock_auths(&[admin.clone()]);
-        EscrowContract::unpause(&env).unwrap();
-        assert!(!EscrowContract::is_paused(&env));
+#[test]
+fn test_migrate_rejects_pre_versioning_orders() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let (admin, treasury, resolver, seller) = create_addresses(&env);
+
+    client.initialize(&admin, &50, &treasury, &resolver);
+
+    let legacy = PreVersioningOrder {
+        id: 1,
+        seller,
+        buyer: None,
+        token: Address::generate(&env),
+        amount: 1000,
+        fiat_currency: Symbol::new(&env, "NGN"),
+        fiat_amount: 1_500_000,
+        rate: 1500,
+        status: OrderStatus::Open,
+        created_at: 0,
+        expires_at: 3600,
+        payment_method: String::from_str(&env, "Bank Transfer"),
+    };
+    env.as_contract(&contract_id, || {
+        env.storage().persistent().set(&DataKey::Order(1), &legacy);
+        env.storage().instance().set(&DataKey::OrderCount, &1u64);
+        env.storage().instance().remove(&DataKey::Version);
+    });
+
+    env.mock_all_auths();
+    assert_eq!(client.version(), 0);
+    assert_eq!(client.try_migrate(), Err(Ok(Error::UnsupportedVersion)));
+
+    // The order is left as it was rather than decoded into the wrong layout
+    let stored: PreVersioningOrder = env.as_contract(&contract_id, || {
+        env.storage().persistent().get(&DataKey::Order(1)).unwrap()
+    });
+    assert_eq!(stored, legacy);
+    assert_eq!(client.version(), 0);
+}
+
+#[test]
+fn test_reputation_after_release_and_reclaim() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 2000);
+
+    env.ledger().set_timestamp(1000);
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let released = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    let abandoned = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+
+    env.ledger().set_timestamp(1600);
+    env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), released).unwrap();
+    });
+
+    env.ledger().set_timestamp(1000 + 1801);
+    env.as_contract(&contract_id, || {
+        EscrowContract::reclaim_expired(env.clone(), abandoned).unwrap();
+    });
+
+    let (seller_reputation, buyer_reputation) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_reputation(env.clone(), seller.clone()),
+            EscrowContract::get_reputation(env.clone(), buyer.clone()),
+        )
+    });
+    assert_eq!(
+        seller_reputation,
+        Reputation {
+            completed_trades: 1,
+            released_trades: 1,
+            total_release_time: 600,
+            ..Reputation::default()
+        }
+    );
+    assert_eq!(
+        buyer_reputation,
+        Reputation {
+            completed_trades: 1,
+            abandoned_trades: 1,
+            ..Reputation::default()
+        }
+    );
+}
+
+#[test]
+fn test_reputation_after_dispute_and_cancel() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let disputed = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), disputed, buyer.clone()).unwrap();
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), disputed, 10_000).unwrap();
+    });
+
+    let cancelled = create_open_order(
+        &env,
+        &contract_id,
+        &seller,
+        &token,
+        "USD",
+        env.ledger().timestamp() + 3600,
+    );
+    env.as_contract(&contract_id, || {
+        EscrowContract::cancel_order(env.clone(), cancelled).unwrap();
+    });
+
+    let (seller_reputation, buyer_reputation) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_reputation(env.clone(), seller.clone()),
+            EscrowContract::get_reputation(env.clone(), buyer.clone()),
+        )
+    });
+    assert_eq!(
+        seller_reputation,
+        Reputation {
+            cancelled_orders: 1,
+            disputes_lost: 1,
+            ..Reputation::default()
+        }
+    );
+    assert_eq!(
+        buyer_reputation,
+        Reputation {
+            disputes_won: 1,
+            ..Reputation::default()
+        }
+    );
+}
+
+#[test]
+fn test_min_reputation_enforced_on_acceptance() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 2000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_open_order(
+        &env,
+        &contract_id,
+        &seller,
+        &token,
+        "USD",
+        env.ledger().timestamp() + 3600,
+    );
+    env.as_contract(&contract_id, || {
+        EscrowContract::set_min_reputation(env.clone(), order_id, 1).unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone())
+    });
+    assert_eq!(result, Err(Error::ReputationTooLow));
+
+    // One completed trade is enough to qualify
+    let first_trade = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::release(env.clone(), first_trade).unwrap();
+    });
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::accept_order(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Locked);
+}
+
+#[test]
+fn test_submit_evidence() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, outsider) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    let receipt = BytesN::from_array(&env, &[1; 32]);
+
+    // Evidence is only accepted once a dispute is open
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::submit_evidence(env.clone(), order_id, buyer.clone(), receipt.clone())
+    });
+    assert_eq!(result, Err(Error::InvalidOrderStatus));
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::submit_evidence(env.clone(), order_id, buyer.clone(), receipt.clone())
+            .unwrap();
+    });
+    let chat_log = BytesN::from_array(&env, &[2; 32]);
+    env.as_contract(&contract_id, || {
+        EscrowContract::submit_evidence(env.clone(), order_id, seller.clone(), chat_log.clone())
+            .unwrap();
+    });
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::submit_evidence(env.clone(), order_id, outsider.clone(), receipt.clone())
+    });
+    assert_eq!(result, Err(Error::Unauthorized));
+
+    let evidence = env.as_contract(&contract_id, || {
+        EscrowContract::get_dispute_evidence(env.clone(), order_id)
+    });
+    assert_eq!(evidence.len(), 2);
+    assert_eq!(evidence.get_unchecked(0).submitted_by, buyer);
+    assert_eq!(evidence.get_unchecked(0).hash, receipt);
+    assert_eq!(evidence.get_unchecked(1).submitted_by, seller);
+    assert_eq!(evidence.get_unchecked(1).hash, chat_log);
+}
+
+#[test]
+fn test_set_arbiters_validation() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+
+    env.mock_all_auths();
+    let invalid = [
+        (Vec::from_array(&env, [first.clone(), second.clone()]), 0),
+        (Vec::from_array(&env, [first.clone(), second.clone()]), 3),
+        (Vec::from_array(&env, [first.clone(), first.clone()]), 1),
+        (Vec::new(&env), 1),
+    ];
+    for (arbiters, threshold) in invalid {
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::set_arbiters(env.clone(), arbiters.clone(), threshold)
+        });
+        assert_eq!(result, Err(Error::InvalidArbiterPanel));
     }
 
-    #[test]
-    fn test_is_paused() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    let arbiters = Vec::from_array(&env, [first.clone(), second.clone()]);
+    env.as_contract(&contract_id, || {
+        EscrowContract::set_arbiters(env.clone(), arbiters.clone(), 2).unwrap();
+    });
+    let panel = env.as_contract(&contract_id, || EscrowContract::get_arbiters(env.clone()));
+    assert_eq!(
+        panel,
+        ArbiterPanel {
+            arbiters,
+            threshold: 2,
+        }
+    );
+}
+
+#[test]
+fn test_arbiter_panel_resolves_dispute() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
+
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let arbiters = Vec::from_array(
+        &env,
+        [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+    );
+
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::set_arbiters(env.clone(), arbiters.clone(), 2).unwrap();
+    });
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, buyer.clone()).unwrap();
+    });
+
+    // The single resolver is bypassed while a panel is configured
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::resolve_dispute(env.clone(), order_id, 10_000)
+    });
+    assert_eq!(result, Err(Error::ArbiterPanelActive));
+
+    let result = env.as_contract(&contract_id, || {
+        EscrowContract::vote_dispute(env.clone(), order_id, resolver.clone(), 10_000)
+    });
+    assert_eq!(result, Err(Error::NotArbiter));
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    // Two arbiters disagree, so nothing is settled yet
+    env.as_contract(&contract_id, || {
+        EscrowContract::vote_dispute(env.clone(), order_id, arbiters.get_unchecked(0), 10_000)
+            .unwrap();
+    });
+    env.as_contract(&contract_id, || {
+        EscrowContract::vote_dispute(env.clone(), order_id, arbiters.get_unchecked(1), 5_000)
+            .unwrap();
+    });
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Disputed);
 
-        assert!(!EscrowContract::is_paused(&env));
+    // The third vote reaches two of three on a full refund to the buyer
+    env.as_contract(&contract_id, || {
+        EscrowContract::vote_dispute(env.clone(), order_id, arbiters.get_unchecked(2), 10_000)
+            .unwrap();
+    });
+
+    let (order, dispute, votes) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_order(env.clone(), order_id).unwrap(),
+            EscrowContract::get_dispute(env.clone(), order_id).unwrap(),
+            EscrowContract::get_dispute_votes(env.clone(), order_id),
+        )
+    });
+    assert_eq!(order.status, OrderStatus::Resolved);
+    assert_eq!(votes.len(), 3);
+    match dispute.outcome {
+        DisputeOutcome::Resolved(resolution) => {
+            assert_eq!(resolution.resolver, arbiters.get_unchecked(2));
+            assert_eq!(resolution.buyer_share_bps, 10_000);
+        }
+        DisputeOutcome::Pending => panic!("dispute should be resolved"),
     }
 
-    #[test]
-    fn test_get_admin() {
-        let env = create_env();
-        let (admin, treasury, resolver, _) = create_addresses(&env);
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&buyer), 995);
+    assert_eq!(token_client.balance(&treasury), 5);
+}
+
+#[test]
+fn test_arbiter_changes_vote_after_split() {
+    let env = create_env();
+    let contract_id = env.register_contract(None, EscrowContract);
+    let (admin, treasury, resolver, _) = create_addresses(&env);
 
-        EscrowContract::initialize(&env, admin.clone(), 50, treasury.clone(), resolver.clone()).unwrap();
+    env.as_contract(&contract_id, || {
+        EscrowContract::initialize(
+            env.clone(),
+            admin.clone(),
+            50,
+            treasury.clone(),
+            resolver.clone(),
+        )
+        .unwrap();
+    });
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token(&env, &token_admin, &seller, 1000);
+    let arbiters = Vec::from_array(
+        &env,
+        [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+    );
 
-        let result = EscrowContract::get_admin(&env);
-        assert_eq!(result, Ok(admin));
+    env.mock_all_auths_allowing_non_root_auth();
+    allow_token(&env, &contract_id, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::set_arbiters(env.clone(), arbiters.clone(), 2).unwrap();
+    });
+    let order_id = create_locked_order(&env, &contract_id, &seller, &buyer, &token);
+    env.as_contract(&contract_id, || {
+        EscrowContract::raise_dispute(env.clone(), order_id, seller.clone()).unwrap();
+    });
+
+    // Every arbiter picks a different share, so no two agree
+    for (arbiter, share) in arbiters.iter().zip([10_000, 5_000, 0]) {
+        env.as_contract(&contract_id, || {
+            EscrowContract::vote_dispute(env.clone(), order_id, arbiter.clone(), share).unwrap();
+        });
     }
-}
\ No newline at end of file
+    let order = env.as_contract(&contract_id, || {
+        EscrowContract::get_order(env.clone(), order_id).unwrap()
+    });
+    assert_eq!(order.status, OrderStatus::Disputed);
+
+    // The middle arbiter comes round to a full refund to the seller
+    env.as_contract(&contract_id, || {
+        EscrowContract::vote_dispute(env.clone(), order_id, arbiters.get_unchecked(1), 0).unwrap();
+    });
+
+    let (order, votes) = env.as_contract(&contract_id, || {
+        (
+            EscrowContract::get_order(env.clone(), order_id).unwrap(),
+            EscrowContract::get_dispute_votes(env.clone(), order_id),
+        )
+    });
+    assert_eq!(order.status, OrderStatus::Resolved);
+    assert_eq!(votes.len(), 3);
+    assert_eq!(votes.get(arbiters.get_unchecked(1)), Some(0));
+
+    let token_client = token::Client::new(&env, &token);
+    assert_eq!(token_client.balance(&seller), 995);
+    assert_eq!(token_client.balance(&buyer), 0);
+    assert_eq!(token_client.balance(&treasury), 5);
+}
@@ -14,6 +14,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         request_timeout: std::time::Duration::from_secs(10),
         max_retries: 3,
         health_check_interval: std::time::Duration::from_secs(30),
+        secret_key: None,
     };
 
     let client = StellarClient::new(config)?;
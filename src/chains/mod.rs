@@ -1 +1,5 @@
 pub mod stellar;
+
+/// Client and types for the Soroban escrow contract, generated from its WASM
+#[cfg(feature = "escrow")]
+pub use escrow_bindings as escrow;
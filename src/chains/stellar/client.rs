@@ -1,23 +1,27 @@
 use crate::chains::stellar::{
     config::StellarConfig,
     errors::{StellarError, StellarResult},
+    transaction::{
+        envelope_hash, envelope_to_xdr, StellarKeypair, StellarOperation, TransactionBuilder,
+        TransactionMemo,
+    },
     types::{
-        extract_afri_balance, is_valid_stellar_address, HealthStatus, HorizonAccount,
-        StellarAccountInfo,
+        extract_afri_balance, HealthStatus, HorizonAccount, HorizonProblem, StellarAccountInfo,
+        StellarAddress, TransactionSubmission,
     },
 };
 use reqwest::Client;
 use std::time::{Duration, Instant};
+use stellar_xdr::curr::{Transaction, TransactionEnvelope};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
-#[allow(dead_code)]
 pub struct StellarClient {
     http_client: Client,
     config: StellarConfig,
+    keypair: Option<StellarKeypair>,
 }
 
-#[allow(dead_code)]
 impl StellarClient {
     pub fn new(config: StellarConfig) -> StellarResult<Self> {
         config
@@ -32,6 +36,12 @@ impl StellarClient {
                 StellarError::config_error(format!("Failed to create HTTP client: {}", e))
             })?;
 
+        let keypair = config
+            .secret_key
+            .as_deref()
+            .map(StellarKeypair::from_secret)
+            .transpose()?;
+
         info!(
             "Stellar client initialized for {:?} network with URL: {}",
             config.network,
@@ -41,13 +51,13 @@ impl StellarClient {
         Ok(Self {
             http_client,
             config,
+            keypair,
         })
     }
 
     pub async fn get_account(&self, address: &str) -> StellarResult<StellarAccountInfo> {
-        if !is_valid_stellar_address(address) {
-            return Err(StellarError::invalid_address(address));
-        }
+        StellarAddress::parse_account(address)
+            .map_err(|e| StellarError::invalid_address(address, e))?;
 
         debug!("Fetching account details for address: {}", address);
 
@@ -92,9 +102,8 @@ impl StellarClient {
     }
 
     pub async fn account_exists(&self, address: &str) -> StellarResult<bool> {
-        if !is_valid_stellar_address(address) {
-            return Err(StellarError::invalid_address(address));
-        }
+        StellarAddress::parse_account(address)
+            .map_err(|e| StellarError::invalid_address(address, e))?;
 
         debug!("Checking if account exists: {}", address);
 
@@ -231,6 +240,159 @@ impl StellarClient {
         }
     }
 
+    /// Starts a transaction from `source`, using its current sequence number from Horizon
+    pub async fn transaction_builder(&self, source: &str) -> StellarResult<TransactionBuilder> {
+        let account = self.get_account(source).await?;
+        Ok(TransactionBuilder::new(&account))
+    }
+
+    /// Signs with the configured secret key for this client's network
+    pub fn sign_transaction(&self, transaction: Transaction) -> StellarResult<TransactionEnvelope> {
+        self.signer()?
+            .sign(transaction, self.config.network.network_passphrase())
+    }
+
+    /// Submits a signed envelope. A timeout yields `TransactionPending`: resubmit this same
+    /// envelope or look its hash up with `get_transaction` rather than building a new one
+    pub async fn submit_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+    ) -> StellarResult<TransactionSubmission> {
+        let hash = envelope_hash(envelope, self.config.network.network_passphrase())?;
+        let tx = envelope_to_xdr(envelope)?;
+        let url = format!("{}/transactions", self.config.network.horizon_url());
+
+        debug!("Submitting transaction {} to Horizon", hash);
+
+        let response = match timeout(
+            self.config.request_timeout,
+            self.http_client.post(&url).form(&[("tx", tx)]).send(),
+        )
+        .await
+        {
+            Ok(Ok(response)) => response,
+            // Nothing reached Horizon, so the envelope cannot have been applied
+            Ok(Err(e)) if e.is_connect() => return Err(e.into()),
+            Ok(Err(_)) | Err(_) => {
+                warn!("Submission of transaction {} timed out", hash);
+                return Err(StellarError::transaction_pending(hash));
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            // The transaction was applied even if the response can't be read
+            let submission: TransactionSubmission = response.json().await.map_err(|e| {
+                warn!("Unreadable response for transaction {}: {}", hash, e);
+                StellarError::transaction_pending(hash.clone())
+            })?;
+            info!(
+                "Transaction {} included in ledger {}",
+                submission.hash, submission.ledger
+            );
+            return Ok(submission);
+        }
+
+        match status {
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Err(StellarError::RateLimitError),
+            // Horizon gave up waiting or failed after forwarding; the transaction may still be
+            // included later
+            status if status.is_server_error() => {
+                warn!("Horizon returned {} for transaction {}", status, hash);
+                Err(StellarError::transaction_pending(hash))
+            }
+            _ => {
+                let problem: HorizonProblem = response.json().await.map_err(|e| {
+                    StellarError::network_error(format!("Horizon API error {}: {}", status, e))
+                })?;
+                let error = match problem.extras.and_then(|extras| extras.result_codes) {
+                    Some(codes) => {
+                        StellarError::from_result_codes(&codes.transaction, &codes.operations)
+                    }
+                    None => StellarError::network_error(format!(
+                        "Horizon API error {}: {}",
+                        status,
+                        problem.detail.unwrap_or(problem.title)
+                    )),
+                };
+
+                // A resubmitted envelope that was already applied fails on its used sequence number
+                if matches!(error, StellarError::BadSequence) {
+                    if let Some(submission) = self.get_transaction(&hash).await? {
+                        info!(
+                            "Transaction {} was already included in ledger {}",
+                            submission.hash, submission.ledger
+                        );
+                        return Ok(submission);
+                    }
+                }
+
+                warn!("Transaction {} submission failed: {}", hash, error);
+                Err(error)
+            }
+        }
+    }
+
+    /// Looks up a transaction by hash; `None` if Horizon has no record of it
+    pub async fn get_transaction(
+        &self,
+        hash: &str,
+    ) -> StellarResult<Option<TransactionSubmission>> {
+        let url = format!(
+            "{}/transactions/{}",
+            self.config.network.horizon_url(),
+            hash
+        );
+
+        let response = timeout(
+            self.config.request_timeout,
+            self.http_client.get(&url).send(),
+        )
+        .await
+        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))??;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Err(StellarError::RateLimitError),
+            status if status.is_success() => response
+                .json()
+                .await
+                .map(Some)
+                .map_err(|e| StellarError::network_error(format!("JSON parsing error: {}", e))),
+            status => Err(StellarError::network_error(format!(
+                "Horizon API error: {}",
+                status
+            ))),
+        }
+    }
+
+    /// Builds, signs and submits `operations` from the configured signing account.
+    /// Each call uses a fresh sequence number, so on `TransactionPending` check the hash with
+    /// `get_transaction` instead of calling this again
+    pub async fn submit_operations(
+        &self,
+        operations: Vec<StellarOperation>,
+        memo: TransactionMemo,
+    ) -> StellarResult<TransactionSubmission> {
+        let source = self.signer()?.public_key();
+        let transaction = operations
+            .into_iter()
+            .fold(
+                self.transaction_builder(&source).await?.memo(memo),
+                TransactionBuilder::add_operation,
+            )
+            .build()?;
+
+        let envelope = self.sign_transaction(transaction)?;
+        self.submit_transaction(&envelope).await
+    }
+
+    fn signer(&self) -> StellarResult<&StellarKeypair> {
+        self.keypair.as_ref().ok_or_else(|| {
+            StellarError::config_error("No secret key configured for signing transactions")
+        })
+    }
+
     pub fn config(&self) -> &StellarConfig {
         &self.config
     }
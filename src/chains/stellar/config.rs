@@ -25,12 +25,30 @@ impl StellarNetwork {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StellarConfig {
     pub network: StellarNetwork,
     pub request_timeout: Duration,
     pub max_retries: u32,
     pub health_check_interval: Duration,
+    /// `S...` seed the client signs transactions with; read-only clients leave it unset
+    #[serde(default, skip_serializing)]
+    pub secret_key: Option<String>,
+}
+
+impl std::fmt::Debug for StellarConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StellarConfig")
+            .field("network", &self.network)
+            .field("request_timeout", &self.request_timeout)
+            .field("max_retries", &self.max_retries)
+            .field("health_check_interval", &self.health_check_interval)
+            .field(
+                "secret_key",
+                &self.secret_key.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
 }
 
 impl Default for StellarConfig {
@@ -40,6 +58,7 @@ impl Default for StellarConfig {
             request_timeout: Duration::from_secs(15),
             max_retries: 3,
             health_check_interval: Duration::from_secs(30),
+            secret_key: None,
         }
     }
 }
@@ -88,11 +107,16 @@ impl StellarConfig {
                 Duration::from_secs(30)
             });
 
+        let secret_key = std::env::var("STELLAR_SECRET_KEY")
+            .ok()
+            .filter(|s| !s.is_empty());
+
         Ok(Self {
             network,
             request_timeout,
             max_retries,
             health_check_interval,
+            secret_key,
         })
     }
 
@@ -109,6 +133,12 @@ impl StellarConfig {
             anyhow::bail!("Health check interval must be greater than 0");
         }
 
+        if let Some(secret_key) = &self.secret_key {
+            if stellar_strkey::ed25519::PrivateKey::from_string(secret_key).is_err() {
+                anyhow::bail!("STELLAR_SECRET_KEY is not a valid Stellar secret key");
+            }
+        }
+
         info!(
             "Stellar configuration validated - Network: {:?}, Timeout: {:?}, Max retries: {}",
             self.network, self.request_timeout, self.max_retries
@@ -8,8 +8,8 @@ pub enum StellarError {
     #[error("Account not found: {address}")]
     AccountNotFound { address: String },
 
-    #[error("Invalid Stellar address: {address}")]
-    InvalidAddress { address: String },
+    #[error("Invalid Stellar address {address}: {reason}")]
+    InvalidAddress { address: String, reason: String },
 
     #[error("Network error: {message}")]
     NetworkError { message: String },
@@ -31,6 +31,46 @@ pub enum StellarError {
 
     #[error("Unexpected error: {message}")]
     UnexpectedError { message: String },
+
+    #[error("Transaction build error: {message}")]
+    TransactionBuildError { message: String },
+
+    #[error("Signing error: {message}")]
+    SigningError { message: String },
+
+    #[error("Transaction rejected: sequence number does not match the source account")]
+    BadSequence,
+
+    #[error("Transaction rejected: missing or invalid signatures")]
+    BadAuth,
+
+    #[error("Transaction rejected: fee is below the network minimum")]
+    InsufficientFee,
+
+    #[error("Insufficient balance to cover the transaction")]
+    InsufficientBalance,
+
+    #[error("Transaction expired before it was included in a ledger")]
+    TransactionExpired,
+
+    #[error("Destination account does not exist")]
+    DestinationNotFound,
+
+    #[error("Account already exists")]
+    AccountAlreadyExists,
+
+    #[error("Missing or unauthorized trustline for the asset")]
+    NoTrustline,
+
+    /// Submission timed out; the transaction may still be applied, so look up `hash` before resending
+    #[error("Transaction {hash} was submitted but its outcome is unknown")]
+    TransactionPending { hash: String },
+
+    #[error("Transaction failed: {transaction_code} {operation_codes:?}")]
+    TransactionFailed {
+        transaction_code: String,
+        operation_codes: Vec<String>,
+    },
 }
 
 #[allow(dead_code)]
@@ -41,9 +81,10 @@ impl StellarError {
         }
     }
 
-    pub fn invalid_address(address: impl Into<String>) -> Self {
+    pub fn invalid_address(address: impl Into<String>, reason: impl ToString) -> Self {
         Self::InvalidAddress {
             address: address.into(),
+            reason: reason.to_string(),
         }
     }
 
@@ -80,6 +121,47 @@ impl StellarError {
             message: message.into(),
         }
     }
+
+    pub fn transaction_build_error(message: impl Into<String>) -> Self {
+        Self::TransactionBuildError {
+            message: message.into(),
+        }
+    }
+
+    pub fn signing_error(message: impl Into<String>) -> Self {
+        Self::SigningError {
+            message: message.into(),
+        }
+    }
+
+    pub fn transaction_pending(hash: impl Into<String>) -> Self {
+        Self::TransactionPending { hash: hash.into() }
+    }
+
+    /// Maps the `extras.result_codes` of a rejected Horizon submission
+    pub fn from_result_codes(transaction_code: &str, operation_codes: &[String]) -> Self {
+        let failed_operation = operation_codes
+            .iter()
+            .map(String::as_str)
+            .find(|code| *code != "op_success");
+
+        match (transaction_code, failed_operation) {
+            ("tx_bad_seq", _) => Self::BadSequence,
+            ("tx_bad_auth" | "tx_bad_auth_extra", _) => Self::BadAuth,
+            ("tx_insufficient_fee", _) => Self::InsufficientFee,
+            ("tx_insufficient_balance", _) => Self::InsufficientBalance,
+            ("tx_too_late", _) => Self::TransactionExpired,
+            ("tx_failed", Some("op_underfunded" | "op_low_reserve")) => Self::InsufficientBalance,
+            ("tx_failed", Some("op_no_destination")) => Self::DestinationNotFound,
+            ("tx_failed", Some("op_already_exists")) => Self::AccountAlreadyExists,
+            ("tx_failed", Some("op_no_trust" | "op_src_no_trust" | "op_not_authorized"))
+            | ("tx_failed", Some("op_src_not_authorized")) => Self::NoTrustline,
+            _ => Self::TransactionFailed {
+                transaction_code: transaction_code.to_string(),
+                operation_codes: operation_codes.to_vec(),
+            },
+        }
+    }
 }
 
 impl From<Box<dyn std::error::Error + Send + Sync>> for StellarError {
@@ -1,6 +1,7 @@
 pub mod client;
 pub mod config;
 pub mod errors;
+pub mod transaction;
 pub mod types;
 
 #[cfg(test)]
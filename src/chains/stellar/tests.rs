@@ -4,9 +4,19 @@ mod tests {
     use crate::chains::stellar::{
         client::StellarClient,
         config::{StellarConfig, StellarNetwork},
-        types::is_valid_stellar_address,
+        transaction::{
+            envelope_hash, envelope_to_xdr, transaction_hash, StellarAsset, StellarKeypair,
+            StellarOperation, TransactionBuilder, TransactionMemo,
+        },
+        types::{
+            is_valid_stellar_address, AccountFlags, StellarAccountInfo, StellarAddress,
+            StellarAddressError, Thresholds,
+        },
     };
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use std::collections::HashMap;
     use std::time::Duration;
+    use stellar_xdr::curr::{Limits, Memo, OperationBody, ReadXdr, TransactionEnvelope};
 
     fn test_config() -> StellarConfig {
         StellarConfig {
@@ -14,6 +24,35 @@ mod tests {
             request_timeout: Duration::from_secs(15),
             max_retries: 3,
             health_check_interval: Duration::from_secs(30),
+            secret_key: None,
+        }
+    }
+
+    fn test_secret() -> String {
+        stellar_strkey::ed25519::PrivateKey([7; 32]).to_string()
+    }
+
+    fn test_account(account_id: &str, sequence: i64) -> StellarAccountInfo {
+        StellarAccountInfo {
+            account_id: account_id.to_string(),
+            sequence,
+            subentry_count: 0,
+            thresholds: Thresholds {
+                low_threshold: 0,
+                med_threshold: 0,
+                high_threshold: 0,
+            },
+            flags: AccountFlags {
+                auth_required: false,
+                auth_revocable: false,
+                auth_immutable: false,
+                auth_clawback_enabled: false,
+            },
+            balances: vec![],
+            signers: vec![],
+            data: HashMap::new(),
+            last_modified_ledger: 0,
+            created_at: String::new(),
         }
     }
 
@@ -30,6 +69,74 @@ mod tests {
 
         let wrong_length = "GD5DJQDQKNR7DSXJVNJTV3P5JJH4KJVTI2JZNYUYIIKHTDNJQXECM4J";
         assert!(!is_valid_stellar_address(wrong_length));
+
+        let lowercase = TEST_ADDRESS.to_lowercase();
+        assert!(!is_valid_stellar_address(&lowercase));
+    }
+
+    #[test]
+    fn test_stellar_address_checksum() {
+        // Flip the last character so only the CRC16 checksum is wrong
+        let mut bad_checksum = TEST_ADDRESS[..55].to_string();
+        bad_checksum.push(if TEST_ADDRESS.ends_with('A') { 'B' } else { 'A' });
+
+        assert_eq!(
+            StellarAddress::parse(&bad_checksum),
+            Err(StellarAddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            StellarAddress::parse("G1234567890"),
+            Err(StellarAddressError::InvalidCharacters)
+        );
+        assert_eq!(
+            StellarAddress::parse(&TEST_ADDRESS[..55]),
+            Err(StellarAddressError::InvalidLength('G', 56))
+        );
+        assert_eq!(StellarAddress::parse(""), Err(StellarAddressError::Empty));
+    }
+
+    #[test]
+    fn test_stellar_address_rejects_secret_keys() {
+        let secret = test_secret();
+        assert_eq!(
+            StellarAddress::parse(&secret),
+            Err(StellarAddressError::SecretKey)
+        );
+        assert!(!is_valid_stellar_address(&secret));
+    }
+
+    #[test]
+    fn test_stellar_address_variants_round_trip() {
+        let account = StellarAddress::parse(TEST_ADDRESS).expect("valid account");
+        assert!(matches!(account, StellarAddress::Account(_)));
+        assert_eq!(account.to_string(), TEST_ADDRESS);
+
+        let key = account.ed25519().unwrap();
+        let muxed = stellar_strkey::ed25519::MuxedAccount {
+            ed25519: key,
+            id: 42,
+        }
+        .to_string();
+        let parsed = StellarAddress::parse(&muxed).expect("valid muxed account");
+        assert_eq!(
+            parsed,
+            StellarAddress::MuxedAccount {
+                ed25519: key,
+                id: 42
+            }
+        );
+        assert_eq!(parsed.to_string(), muxed);
+        assert_eq!(parsed.base_account(), Some(account));
+        assert_eq!(
+            StellarAddress::parse_account(&muxed),
+            Err(StellarAddressError::NotAnAccount)
+        );
+
+        let contract = stellar_strkey::Contract([9; 32]).to_string();
+        let parsed: StellarAddress = contract.parse().expect("valid contract");
+        assert_eq!(parsed, StellarAddress::Contract([9; 32]));
+        assert_eq!(parsed.to_string(), contract);
+        assert_eq!(parsed.base_account(), None);
     }
 
     #[tokio::test]
@@ -69,7 +176,7 @@ mod tests {
         let client = StellarClient::new(config).expect("Failed to create client");
 
         // Use a valid format but nonexistent address
-        let nonexistent_address = "GAAAAAAAACGC6AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let nonexistent_address = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
 
         let result = client.get_account(nonexistent_address).await;
         // This test verifies we get an error for nonexistent accounts
@@ -220,4 +327,183 @@ mod tests {
             "Public Global Stellar Network ; September 2015"
         );
     }
+
+    #[test]
+    fn test_build_transaction_from_account_sequence() {
+        let account = test_account(TEST_ADDRESS, 41);
+        let transaction = TransactionBuilder::new(&account)
+            .memo(TransactionMemo::Id(7))
+            .add_operation(StellarOperation::Payment {
+                destination: TEST_ADDRESS.to_string(),
+                asset: StellarAsset::credit("AFRI", TEST_ADDRESS),
+                amount: 10_000_000,
+            })
+            .add_operation(StellarOperation::ChangeTrust {
+                asset: StellarAsset::credit("USDC", TEST_ADDRESS),
+                limit: None,
+            })
+            .add_operation(StellarOperation::ManageData {
+                name: "kyc_level".to_string(),
+                value: Some(b"2".to_vec()),
+            })
+            .build()
+            .expect("Failed to build transaction");
+
+        assert_eq!(transaction.seq_num.0, 42);
+        assert_eq!(transaction.fee, 300);
+        assert_eq!(transaction.memo, Memo::Id(7));
+        assert_eq!(transaction.operations.len(), 3);
+        assert!(matches!(
+            transaction.operations[0].body,
+            OperationBody::Payment(_)
+        ));
+    }
+
+    #[test]
+    fn test_build_transaction_rejects_invalid_input() {
+        let account = test_account(TEST_ADDRESS, 1);
+
+        let empty = TransactionBuilder::new(&account).build();
+        assert!(matches!(
+            empty,
+            Err(StellarError::TransactionBuildError { .. })
+        ));
+
+        let long_memo = TransactionBuilder::new(&account)
+            .memo(TransactionMemo::Text("x".repeat(29)))
+            .add_operation(StellarOperation::CreateAccount {
+                destination: TEST_ADDRESS.to_string(),
+                starting_balance: 10_000_000,
+            })
+            .build();
+        assert!(matches!(
+            long_memo,
+            Err(StellarError::TransactionBuildError { .. })
+        ));
+
+        let bad_destination = TransactionBuilder::new(&account)
+            .add_operation(StellarOperation::CreateAccount {
+                destination: "INVALID_ADDRESS".to_string(),
+                starting_balance: 10_000_000,
+            })
+            .build();
+        assert!(matches!(
+            bad_destination,
+            Err(StellarError::InvalidAddress { .. })
+        ));
+
+        let native_trustline = TransactionBuilder::new(&account)
+            .add_operation(StellarOperation::ChangeTrust {
+                asset: StellarAsset::Native,
+                limit: None,
+            })
+            .build();
+        assert!(matches!(
+            native_trustline,
+            Err(StellarError::TransactionBuildError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sign_transaction_envelope() {
+        let keypair = StellarKeypair::from_secret(&test_secret()).expect("Invalid secret");
+        let source = keypair.public_key();
+        assert!(source.starts_with('G'));
+
+        let transaction = TransactionBuilder::new(&test_account(&source, 100))
+            .add_operation(StellarOperation::Payment {
+                destination: TEST_ADDRESS.to_string(),
+                asset: StellarAsset::Native,
+                amount: 5_000_000,
+            })
+            .build()
+            .expect("Failed to build transaction");
+
+        let passphrase = StellarNetwork::Testnet.network_passphrase();
+        let hash = transaction_hash(&transaction, passphrase).unwrap();
+        let envelope = keypair.sign(transaction, passphrase).unwrap();
+
+        // The base64 XDR must decode back to the same envelope
+        let xdr = envelope_to_xdr(&envelope).unwrap();
+        let decoded = TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).unwrap();
+        assert_eq!(decoded, envelope);
+        assert_eq!(
+            envelope_hash(&envelope, passphrase).unwrap(),
+            hex::encode(hash)
+        );
+
+        let TransactionEnvelope::Tx(envelope) = envelope else {
+            panic!("Expected a v1 transaction envelope");
+        };
+        assert_eq!(envelope.signatures.len(), 1);
+
+        let public_key = stellar_strkey::ed25519::PublicKey::from_string(&source).unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public_key.0).unwrap();
+        let signature =
+            Signature::from_slice(envelope.signatures[0].signature.0.as_slice()).unwrap();
+        assert!(verifying_key.verify(&hash, &signature).is_ok());
+        assert_eq!(envelope.signatures[0].hint.0, public_key.0[28..]);
+    }
+
+    #[test]
+    fn test_invalid_secret_key_rejected() {
+        assert!(matches!(
+            StellarKeypair::from_secret(TEST_ADDRESS),
+            Err(StellarError::SigningError { .. })
+        ));
+
+        let mut config = test_config();
+        config.secret_key = Some("SNOTAKEY".to_string());
+        assert!(config.validate().is_err());
+
+        config.secret_key = Some(test_secret());
+        assert!(config.validate().is_ok());
+        assert!(!format!("{:?}", config).contains(&test_secret()));
+    }
+
+    #[test]
+    fn test_horizon_result_codes_mapping() {
+        let ops = |codes: &[&str]| codes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        assert!(matches!(
+            StellarError::from_result_codes("tx_bad_seq", &[]),
+            StellarError::BadSequence
+        ));
+        assert!(matches!(
+            StellarError::from_result_codes("tx_insufficient_fee", &[]),
+            StellarError::InsufficientFee
+        ));
+        assert!(matches!(
+            StellarError::from_result_codes("tx_failed", &ops(&["op_success", "op_underfunded"])),
+            StellarError::InsufficientBalance
+        ));
+        assert!(matches!(
+            StellarError::from_result_codes("tx_failed", &ops(&["op_no_trust"])),
+            StellarError::NoTrustline
+        ));
+        assert!(matches!(
+            StellarError::from_result_codes("tx_failed", &ops(&["op_no_destination"])),
+            StellarError::DestinationNotFound
+        ));
+        match StellarError::from_result_codes("tx_failed", &ops(&["op_line_full"])) {
+            StellarError::TransactionFailed {
+                transaction_code,
+                operation_codes,
+            } => {
+                assert_eq!(transaction_code, "tx_failed");
+                assert_eq!(operation_codes, ops(&["op_line_full"]));
+            }
+            other => panic!("Unexpected error: {}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_operations_requires_secret_key() {
+        let client = StellarClient::new(test_config()).expect("Failed to create client");
+
+        let result = client
+            .submit_operations(vec![], TransactionMemo::None)
+            .await;
+        assert!(matches!(result, Err(StellarError::ConfigError { .. })));
+    }
 }
@@ -0,0 +1,425 @@
+use crate::chains::stellar::{
+    errors::{StellarError, StellarResult},
+    types::{StellarAccountInfo, StellarAddress, StellarAddressError},
+};
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use stellar_xdr::curr::{
+    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, ChangeTrustAsset,
+    ChangeTrustOp, CreateAccountOp, DataValue, DecoratedSignature, Hash, Limits, ManageDataOp,
+    Memo, MuxedAccount, MuxedAccountMed25519, Operation, OperationBody, PaymentOp, Preconditions,
+    PublicKey, SequenceNumber, Signature, SignatureHint, String64, TimeBounds, TimePoint,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
+
+/// Minimum fee per operation, in stroops
+pub const BASE_FEE: u32 = 100;
+
+/// How long a built transaction stays valid for submission
+pub const DEFAULT_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Trustline limit used when none is given: the largest amount Stellar can represent
+pub const MAX_TRUST_LIMIT: i64 = i64::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarAsset {
+    Native,
+    Credit { code: String, issuer: String },
+}
+
+impl StellarAsset {
+    pub fn credit(code: impl Into<String>, issuer: impl Into<String>) -> Self {
+        Self::Credit {
+            code: code.into(),
+            issuer: issuer.into(),
+        }
+    }
+
+    fn to_xdr(&self) -> StellarResult<Asset> {
+        match self {
+            StellarAsset::Native => Ok(Asset::Native),
+            StellarAsset::Credit { code, issuer } => {
+                let issuer = account_id(issuer)?;
+                match asset_code(code)? {
+                    AssetCode::Four(asset_code) => {
+                        Ok(Asset::CreditAlphanum4(AlphaNum4 { asset_code, issuer }))
+                    }
+                    AssetCode::Twelve(asset_code) => {
+                        Ok(Asset::CreditAlphanum12(AlphaNum12 { asset_code, issuer }))
+                    }
+                }
+            }
+        }
+    }
+
+    fn to_change_trust_xdr(&self) -> StellarResult<ChangeTrustAsset> {
+        match self.to_xdr()? {
+            Asset::Native => Err(StellarError::transaction_build_error(
+                "Cannot create a trustline to the native asset",
+            )),
+            Asset::CreditAlphanum4(asset) => Ok(ChangeTrustAsset::CreditAlphanum4(asset)),
+            Asset::CreditAlphanum12(asset) => Ok(ChangeTrustAsset::CreditAlphanum12(asset)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TransactionMemo {
+    #[default]
+    None,
+    Text(String),
+    Id(u64),
+    Hash([u8; 32]),
+}
+
+impl TransactionMemo {
+    fn to_xdr(&self) -> StellarResult<Memo> {
+        match self {
+            TransactionMemo::None => Ok(Memo::None),
+            TransactionMemo::Text(text) => {
+                text.as_str().try_into().map(Memo::Text).map_err(|_| {
+                    StellarError::transaction_build_error("Memo text exceeds 28 bytes")
+                })
+            }
+            TransactionMemo::Id(id) => Ok(Memo::Id(*id)),
+            TransactionMemo::Hash(hash) => Ok(Memo::Hash(Hash(*hash))),
+        }
+    }
+}
+
+/// Operations the backend submits. Amounts are in stroops (1 XLM or AFRI = 10^7 stroops)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarOperation {
+    Payment {
+        destination: String,
+        asset: StellarAsset,
+        amount: i64,
+    },
+    ChangeTrust {
+        asset: StellarAsset,
+        limit: Option<i64>,
+    },
+    CreateAccount {
+        destination: String,
+        starting_balance: i64,
+    },
+    /// Sets the account data entry `name`, or deletes it when `value` is `None`
+    ManageData {
+        name: String,
+        value: Option<Vec<u8>>,
+    },
+}
+
+impl StellarOperation {
+    fn to_xdr(&self) -> StellarResult<Operation> {
+        let body = match self {
+            StellarOperation::Payment {
+                destination,
+                asset,
+                amount,
+            } => {
+                ensure_positive(*amount, "Payment amount")?;
+                OperationBody::Payment(PaymentOp {
+                    destination: muxed_account(destination)?,
+                    asset: asset.to_xdr()?,
+                    amount: *amount,
+                })
+            }
+            StellarOperation::ChangeTrust { asset, limit } => {
+                let limit = limit.unwrap_or(MAX_TRUST_LIMIT);
+                if limit < 0 {
+                    return Err(StellarError::transaction_build_error(
+                        "Trustline limit cannot be negative",
+                    ));
+                }
+                OperationBody::ChangeTrust(ChangeTrustOp {
+                    line: asset.to_change_trust_xdr()?,
+                    limit,
+                })
+            }
+            StellarOperation::CreateAccount {
+                destination,
+                starting_balance,
+            } => {
+                ensure_positive(*starting_balance, "Starting balance")?;
+                OperationBody::CreateAccount(CreateAccountOp {
+                    destination: account_id(destination)?,
+                    starting_balance: *starting_balance,
+                })
+            }
+            StellarOperation::ManageData { name, value } => {
+                if name.is_empty() {
+                    return Err(StellarError::transaction_build_error(
+                        "Data entry name cannot be empty",
+                    ));
+                }
+                let data_name = name.as_str().try_into().map_err(|_| {
+                    StellarError::transaction_build_error("Data entry name exceeds 64 bytes")
+                })?;
+                let data_value = value
+                    .clone()
+                    .map(|value| {
+                        value.try_into().map(DataValue).map_err(|_| {
+                            StellarError::transaction_build_error(
+                                "Data entry value exceeds 64 bytes",
+                            )
+                        })
+                    })
+                    .transpose()?;
+                OperationBody::ManageData(ManageDataOp {
+                    data_name: String64(data_name),
+                    data_value,
+                })
+            }
+        };
+
+        Ok(Operation {
+            source_account: None,
+            body,
+        })
+    }
+}
+
+/// Builds a transaction for a source account fetched from Horizon
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    source_account: String,
+    sequence: i64,
+    base_fee: u32,
+    memo: TransactionMemo,
+    timeout: Duration,
+    operations: Vec<StellarOperation>,
+}
+
+impl TransactionBuilder {
+    /// Uses the sequence number after the account's current one
+    pub fn new(account: &StellarAccountInfo) -> Self {
+        Self {
+            source_account: account.account_id.clone(),
+            sequence: account.sequence + 1,
+            base_fee: BASE_FEE,
+            memo: TransactionMemo::None,
+            timeout: DEFAULT_TRANSACTION_TIMEOUT,
+            operations: Vec::new(),
+        }
+    }
+
+    pub fn base_fee(mut self, stroops: u32) -> Self {
+        self.base_fee = stroops;
+        self
+    }
+
+    pub fn memo(mut self, memo: TransactionMemo) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn add_operation(mut self, operation: StellarOperation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    pub fn build(self) -> StellarResult<Transaction> {
+        if self.operations.is_empty() {
+            return Err(StellarError::transaction_build_error(
+                "Transaction needs at least one operation",
+            ));
+        }
+
+        let operations = self
+            .operations
+            .iter()
+            .map(StellarOperation::to_xdr)
+            .collect::<StellarResult<Vec<_>>>()?;
+        let fee = u32::try_from(operations.len())
+            .ok()
+            .and_then(|count| count.checked_mul(self.base_fee))
+            .ok_or_else(|| StellarError::transaction_build_error("Transaction fee overflow"))?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| StellarError::transaction_build_error(e.to_string()))?;
+
+        Ok(Transaction {
+            source_account: muxed_account(&self.source_account)?,
+            fee,
+            seq_num: SequenceNumber(self.sequence),
+            cond: Preconditions::Time(TimeBounds {
+                min_time: TimePoint(0),
+                max_time: TimePoint((now + self.timeout).as_secs()),
+            }),
+            memo: self.memo.to_xdr()?,
+            operations: operations.try_into().map_err(|_| {
+                StellarError::transaction_build_error("Transaction exceeds 100 operations")
+            })?,
+            ext: TransactionExt::V0,
+        })
+    }
+}
+
+/// Ed25519 key pair decoded from an `S...` secret seed
+#[derive(Clone)]
+pub struct StellarKeypair {
+    signing_key: SigningKey,
+}
+
+impl std::fmt::Debug for StellarKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StellarKeypair")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl StellarKeypair {
+    pub fn from_secret(secret: &str) -> StellarResult<Self> {
+        let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret)
+            .map_err(|_| StellarError::signing_error("Invalid Stellar secret key"))?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&seed.0),
+        })
+    }
+
+    /// The `G...` account id for this key pair
+    pub fn public_key(&self) -> String {
+        stellar_strkey::ed25519::PublicKey(self.signing_key.verifying_key().to_bytes()).to_string()
+    }
+
+    /// Signs `transaction` for the network identified by `network_passphrase`
+    pub fn sign(
+        &self,
+        transaction: Transaction,
+        network_passphrase: &str,
+    ) -> StellarResult<TransactionEnvelope> {
+        let hash = transaction_hash(&transaction, network_passphrase)?;
+        let public_key = self.signing_key.verifying_key().to_bytes();
+
+        let mut hint = [0u8; 4];
+        hint.copy_from_slice(&public_key[28..]);
+        let signature = self.signing_key.sign(&hash).to_bytes().to_vec();
+
+        let signature = DecoratedSignature {
+            hint: SignatureHint(hint),
+            signature: Signature(
+                signature
+                    .try_into()
+                    .map_err(|_| StellarError::signing_error("Invalid signature length"))?,
+            ),
+        };
+
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: vec![signature]
+                .try_into()
+                .map_err(|_| StellarError::signing_error("Too many signatures"))?,
+        }))
+    }
+}
+
+/// The hash that signers sign and Horizon reports as the transaction id
+pub fn transaction_hash(
+    transaction: &Transaction,
+    network_passphrase: &str,
+) -> StellarResult<[u8; 32]> {
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(transaction.clone()),
+    };
+    let bytes = payload
+        .to_xdr(Limits::none())
+        .map_err(|e| StellarError::serialization_error(format!("XDR error: {}", e)))?;
+    Ok(Sha256::digest(bytes).into())
+}
+
+/// Hex-encoded hash of a signed envelope, the id Horizon serves it under at `/transactions/{hash}`
+pub fn envelope_hash(
+    envelope: &TransactionEnvelope,
+    network_passphrase: &str,
+) -> StellarResult<String> {
+    match envelope {
+        TransactionEnvelope::Tx(envelope) => {
+            transaction_hash(&envelope.tx, network_passphrase).map(hex::encode)
+        }
+        _ => Err(StellarError::serialization_error(
+            "Only v1 transaction envelopes are supported",
+        )),
+    }
+}
+
+/// Base64 XDR of a signed envelope, as `POST /transactions` expects it
+pub fn envelope_to_xdr(envelope: &TransactionEnvelope) -> StellarResult<String> {
+    envelope
+        .to_xdr_base64(Limits::none())
+        .map_err(|e| StellarError::serialization_error(format!("XDR error: {}", e)))
+}
+
+enum AssetCode {
+    Four(AssetCode4),
+    Twelve(AssetCode12),
+}
+
+fn asset_code(code: &str) -> StellarResult<AssetCode> {
+    if code.is_empty() || code.len() > 12 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(StellarError::transaction_build_error(format!(
+            "Invalid asset code: {}",
+            code
+        )));
+    }
+
+    if code.len() <= 4 {
+        let mut bytes = [0u8; 4];
+        bytes[..code.len()].copy_from_slice(code.as_bytes());
+        Ok(AssetCode::Four(AssetCode4(bytes)))
+    } else {
+        let mut bytes = [0u8; 12];
+        bytes[..code.len()].copy_from_slice(code.as_bytes());
+        Ok(AssetCode::Twelve(AssetCode12(bytes)))
+    }
+}
+
+fn account_id(address: &str) -> StellarResult<AccountId> {
+    match StellarAddress::parse_account(address) {
+        Ok(StellarAddress::Account(key)) => {
+            Ok(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))))
+        }
+        Ok(_) => Err(StellarError::invalid_address(
+            address,
+            StellarAddressError::NotAnAccount,
+        )),
+        Err(e) => Err(StellarError::invalid_address(address, e)),
+    }
+}
+
+fn muxed_account(address: &str) -> StellarResult<MuxedAccount> {
+    match StellarAddress::parse(address) {
+        Ok(StellarAddress::Account(key)) => Ok(MuxedAccount::Ed25519(Uint256(key))),
+        Ok(StellarAddress::MuxedAccount { ed25519, id }) => {
+            Ok(MuxedAccount::MuxedEd25519(MuxedAccountMed25519 {
+                id,
+                ed25519: Uint256(ed25519),
+            }))
+        }
+        Ok(StellarAddress::Contract(_)) => Err(StellarError::invalid_address(
+            address,
+            StellarAddressError::NotAnAccount,
+        )),
+        Err(e) => Err(StellarError::invalid_address(address, e)),
+    }
+}
+
+fn ensure_positive(amount: i64, what: &str) -> StellarResult<()> {
+    if amount <= 0 {
+        return Err(StellarError::transaction_build_error(format!(
+            "{} must be positive",
+            what
+        )));
+    }
+    Ok(())
+}
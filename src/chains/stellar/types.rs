@@ -83,6 +83,43 @@ pub struct HorizonBalance {
     pub last_modified_ledger: Option<u64>,
 }
 
+/// Horizon's response to an accepted `POST /transactions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionSubmission {
+    pub hash: String,
+    pub ledger: u32,
+    #[serde(default)]
+    pub successful: bool,
+    pub envelope_xdr: String,
+    pub result_xdr: String,
+}
+
+/// Horizon problem document returned when a submission is rejected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonProblem {
+    pub title: String,
+    pub status: u16,
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default)]
+    pub extras: Option<HorizonProblemExtras>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonProblemExtras {
+    #[serde(default)]
+    pub result_codes: Option<HorizonResultCodes>,
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonResultCodes {
+    pub transaction: String,
+    #[serde(default)]
+    pub operations: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthStatus {
     pub is_healthy: bool,
@@ -130,11 +167,122 @@ impl From<HorizonBalance> for AssetBalance {
     }
 }
 
+/// A decoded StrKey address: an account (`G...`), muxed account (`M...`) or contract (`C...`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StellarAddress {
+    Account([u8; 32]),
+    MuxedAccount { ed25519: [u8; 32], id: u64 },
+    Contract([u8; 32]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StellarAddressError {
+    #[error("address is empty")]
+    Empty,
+
+    #[error("address must only contain the characters A-Z and 2-7")]
+    InvalidCharacters,
+
+    #[error("'{0}' addresses must be {1} characters long")]
+    InvalidLength(char, usize),
+
+    #[error("address checksum does not match")]
+    InvalidChecksum,
+
+    #[error("this is a secret key; it must never be shared or used as an address")]
+    SecretKey,
+
+    #[error("'{0}' is not a supported address type")]
+    UnsupportedType(char),
+
+    #[error("expected an account address starting with 'G'")]
+    NotAnAccount,
+}
+
+#[allow(dead_code)]
+impl StellarAddress {
+    /// Decodes and checksums a G, M or C StrKey; secret seeds are rejected outright
+    pub fn parse(address: &str) -> Result<Self, StellarAddressError> {
+        let prefix = address.chars().next().ok_or(StellarAddressError::Empty)?;
+        if !address
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+        {
+            return Err(StellarAddressError::InvalidCharacters);
+        }
+
+        let expected_len = match prefix {
+            'G' | 'C' => 56,
+            'M' => 69,
+            'S' => return Err(StellarAddressError::SecretKey),
+            other => return Err(StellarAddressError::UnsupportedType(other)),
+        };
+        if address.len() != expected_len {
+            return Err(StellarAddressError::InvalidLength(prefix, expected_len));
+        }
+
+        // Characters and length are valid by now, so a decode failure is a bad checksum
+        match stellar_strkey::Strkey::from_string(address) {
+            Ok(stellar_strkey::Strkey::PublicKeyEd25519(key)) => Ok(Self::Account(key.0)),
+            Ok(stellar_strkey::Strkey::MuxedAccountEd25519(muxed)) => Ok(Self::MuxedAccount {
+                ed25519: muxed.ed25519,
+                id: muxed.id,
+            }),
+            Ok(stellar_strkey::Strkey::Contract(contract)) => Ok(Self::Contract(contract.0)),
+            Ok(_) => Err(StellarAddressError::UnsupportedType(prefix)),
+            Err(_) => Err(StellarAddressError::InvalidChecksum),
+        }
+    }
+
+    /// Parses an address that must be a plain `G...` account, as Horizon and the database expect
+    pub fn parse_account(address: &str) -> Result<Self, StellarAddressError> {
+        match Self::parse(address)? {
+            account @ Self::Account(_) => Ok(account),
+            _ => Err(StellarAddressError::NotAnAccount),
+        }
+    }
+
+    /// The underlying account's ed25519 key, for account and muxed account addresses
+    pub fn ed25519(&self) -> Option<[u8; 32]> {
+        match self {
+            Self::Account(key) | Self::MuxedAccount { ed25519: key, .. } => Some(*key),
+            Self::Contract(_) => None,
+        }
+    }
+
+    /// The `G...` address of the underlying account, resolving muxed accounts
+    pub fn base_account(&self) -> Option<StellarAddress> {
+        self.ed25519().map(Self::Account)
+    }
+}
+
+impl std::fmt::Display for StellarAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoded = match self {
+            Self::Account(key) => stellar_strkey::ed25519::PublicKey(*key).to_string(),
+            Self::MuxedAccount { ed25519, id } => stellar_strkey::ed25519::MuxedAccount {
+                ed25519: *ed25519,
+                id: *id,
+            }
+            .to_string(),
+            Self::Contract(hash) => stellar_strkey::Contract(*hash).to_string(),
+        };
+        f.write_str(&encoded)
+    }
+}
+
+impl std::str::FromStr for StellarAddress {
+    type Err = StellarAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Whether `address` is a valid `G...` account address, checksum included
+#[allow(dead_code)]
 pub fn is_valid_stellar_address(address: &str) -> bool {
-    // Basic validation: Stellar addresses are 56 characters starting with 'G'
-    address.len() == 56
-        && address.starts_with('G')
-        && address.chars().all(|c| c.is_ascii_alphanumeric())
+    StellarAddress::parse_account(address).is_ok()
 }
 
 #[allow(dead_code)]
@@ -23,6 +23,8 @@ pub enum DatabaseErrorKind {
     InsufficientBalance { available: String, required: String },
     /// Trustline not found
     TrustlineNotFound { account: String, asset: String },
+    /// Address is not a valid `G...` Stellar account
+    InvalidWalletAddress { address: String, reason: String },
     /// Configuration error
     ConfigError { message: String },
     /// Unknown error
@@ -168,6 +170,9 @@ impl fmt::Display for DatabaseError {
                     account, asset
                 )
             }
+            DatabaseErrorKind::InvalidWalletAddress { address, reason } => {
+                format!("Invalid wallet address '{}': {}", address, reason)
+            }
             DatabaseErrorKind::ConfigError { message } => {
                 format!("Database configuration error: {}", message)
             }
@@ -190,7 +195,9 @@ impl std::error::Error for DatabaseError {}
 #[cfg(feature = "database")]
 impl From<DatabaseError> for crate::error::AppError {
     fn from(err: DatabaseError) -> Self {
-        use crate::error::{AppError, AppErrorKind, DomainError, InfrastructureError};
+        use crate::error::{
+            AppError, AppErrorKind, DomainError, InfrastructureError, ValidationError,
+        };
 
         let kind = match &err.kind {
             DatabaseErrorKind::NotFound { entity, id } => {
@@ -219,6 +226,12 @@ impl From<DatabaseError> for crate::error::AppError {
                     asset: asset.clone(),
                 })
             }
+            DatabaseErrorKind::InvalidWalletAddress { address, reason } => {
+                AppErrorKind::Validation(ValidationError::InvalidWalletAddress {
+                    address: address.clone(),
+                    reason: reason.clone(),
+                })
+            }
             DatabaseErrorKind::UniqueConstraintViolation { .. } => {
                 AppErrorKind::Domain(DomainError::DuplicateTransaction {
                     transaction_id: "unknown".to_string(),
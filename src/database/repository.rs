@@ -1,6 +1,20 @@
-use crate::database::error::DatabaseError;
+use crate::chains::stellar::types::StellarAddress;
+use crate::database::error::{DatabaseError, DatabaseErrorKind};
 use async_trait::async_trait;
 
+/// Checks an address before it is written to a wallet column. The database only
+/// enforces the `^G[A-Z2-7]{55}$` shape, so this also catches bad checksums
+pub fn validate_wallet_address(address: &str) -> Result<(), DatabaseError> {
+    StellarAddress::parse_account(address)
+        .map(|_| ())
+        .map_err(|e| {
+            DatabaseError::new(DatabaseErrorKind::InvalidWalletAddress {
+                address: address.to_string(),
+                reason: e.to_string(),
+            })
+        })
+}
+
 /// Base repository trait defining common database operations
 /// All domain-specific repositories should implement this trait
 #[async_trait]
@@ -1,5 +1,5 @@
 use crate::database::error::DatabaseError;
-use crate::database::repository::{Repository, TransactionalRepository};
+use crate::database::repository::{validate_wallet_address, Repository, TransactionalRepository};
 use async_trait::async_trait;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
@@ -135,6 +135,8 @@ impl TrustlineRepository {
         issuer: &str,
         limit: &str,
     ) -> Result<Trustline, DatabaseError> {
+        validate_wallet_address(account)?;
+        validate_wallet_address(issuer)?;
         let trustline_id = Uuid::new_v4().to_string();
 
         let trustline = sqlx::query_as::<_, Trustline>(
@@ -312,6 +314,8 @@ impl Repository for TrustlineRepository {
     }
 
     async fn insert(&self, entity: &Self::Entity) -> Result<Self::Entity, DatabaseError> {
+        validate_wallet_address(&entity.account)?;
+        validate_wallet_address(&entity.issuer)?;
         sqlx::query_as::<_, Trustline>(
             "INSERT INTO trustlines (id, account, asset_code, balance, limit, issuer, status, created_at, updated_at) 
              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) 
@@ -332,6 +336,8 @@ impl Repository for TrustlineRepository {
     }
 
     async fn update(&self, id: &str, entity: &Self::Entity) -> Result<Self::Entity, DatabaseError> {
+        validate_wallet_address(&entity.account)?;
+        validate_wallet_address(&entity.issuer)?;
         sqlx::query_as::<_, Trustline>(
             "UPDATE trustlines 
              SET account = $1, asset_code = $2, balance = $3, limit = $4, issuer = $5, status = $6, updated_at = NOW() 
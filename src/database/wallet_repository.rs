@@ -1,5 +1,5 @@
 use crate::database::error::DatabaseError;
-use crate::database::repository::{Repository, TransactionalRepository};
+use crate::database::repository::{validate_wallet_address, Repository, TransactionalRepository};
 use async_trait::async_trait;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
@@ -165,6 +165,7 @@ impl WalletRepository {
         account_address: &str,
         initial_balance: &str,
     ) -> Result<Wallet, DatabaseError> {
+        validate_wallet_address(account_address)?;
         let wallet_id = Uuid::new_v4().to_string();
 
         sqlx::query_as::<_, Wallet>(
@@ -287,6 +288,7 @@ impl Repository for WalletRepository {
     }
 
     async fn insert(&self, entity: &Self::Entity) -> Result<Self::Entity, DatabaseError> {
+        validate_wallet_address(&entity.account_address)?;
         sqlx::query_as::<_, Wallet>(
             "INSERT INTO wallets (id, user_id, account_address, balance, created_at, updated_at) 
              VALUES ($1, $2, $3, $4, $5, $6) 
@@ -304,6 +306,7 @@ impl Repository for WalletRepository {
     }
 
     async fn update(&self, id: &str, entity: &Self::Entity) -> Result<Self::Entity, DatabaseError> {
+        validate_wallet_address(&entity.account_address)?;
         sqlx::query_as::<_, Wallet>(
             "UPDATE wallets SET user_id = $1, account_address = $2, balance = $3, updated_at = NOW() 
              WHERE id = $4 
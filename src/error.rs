@@ -9,6 +9,8 @@ use std::fmt;
 
 #[cfg(feature = "database")]
 use crate::chains::stellar::errors::StellarError;
+#[cfg(feature = "database")]
+use crate::chains::stellar::types::StellarAddress;
 
 /// AFRI-specific error codes for programmatic handling
 #[cfg(feature = "database")]
@@ -144,6 +146,17 @@ pub enum ValidationError {
     },
 }
 
+#[cfg(feature = "database")]
+impl ValidationError {
+    /// Parses a user-supplied wallet address, which must be a `G...` account with a valid checksum
+    pub fn parse_wallet_address(address: &str) -> Result<StellarAddress, Self> {
+        StellarAddress::parse_account(address).map_err(|e| Self::InvalidWalletAddress {
+            address: address.to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
 /// Unified application error type
 #[cfg(feature = "database")]
 #[derive(Debug, Clone)]
@@ -415,15 +428,13 @@ impl From<StellarError> for AppError {
     fn from(err: StellarError) -> Self {
         use crate::chains::stellar::errors::StellarError as SE;
 
+        let description = err.to_string();
         let kind = match err {
             SE::AccountNotFound { address } => AppErrorKind::Domain(DomainError::WalletNotFound {
                 wallet_address: address,
             }),
-            SE::InvalidAddress { address } => {
-                AppErrorKind::Validation(ValidationError::InvalidWalletAddress {
-                    address,
-                    reason: "Invalid Stellar address format".to_string(),
-                })
+            SE::InvalidAddress { address, reason } => {
+                AppErrorKind::Validation(ValidationError::InvalidWalletAddress { address, reason })
             }
             SE::RateLimitError => AppErrorKind::External(ExternalError::RateLimit {
                 service: "Stellar".to_string(),
@@ -442,8 +453,37 @@ impl From<StellarError> for AppError {
             SE::ConfigError { message } => {
                 AppErrorKind::Infrastructure(InfrastructureError::Configuration { message })
             }
-            _ => AppErrorKind::External(ExternalError::Blockchain {
-                message: err.to_string(),
+            // The network rejected the envelope outright, so it can never be applied
+            SE::TransactionExpired | SE::InsufficientFee => {
+                AppErrorKind::External(ExternalError::Blockchain {
+                    message: description,
+                    is_retryable: true,
+                })
+            }
+            // An earlier envelope may have consumed the sequence number, or may still land
+            SE::BadSequence | SE::TransactionPending { .. } => {
+                AppErrorKind::External(ExternalError::Blockchain {
+                    message: description,
+                    is_retryable: false,
+                })
+            }
+            // Horizon result codes don't carry the balances or the destination address
+            SE::InsufficientBalance => AppErrorKind::Domain(DomainError::InsufficientBalance {
+                available: "unknown".to_string(),
+                required: "unknown".to_string(),
+            }),
+            SE::DestinationNotFound => AppErrorKind::Domain(DomainError::WalletNotFound {
+                wallet_address: "unknown".to_string(),
+            }),
+            SE::BadAuth
+            | SE::AccountAlreadyExists
+            | SE::NoTrustline
+            | SE::TransactionFailed { .. }
+            | SE::TransactionBuildError { .. }
+            | SE::SigningError { .. }
+            | SE::HealthCheckError { .. }
+            | SE::SerializationError { .. } => AppErrorKind::External(ExternalError::Blockchain {
+                message: description,
                 is_retryable: false,
             }),
         };
@@ -508,4 +548,27 @@ mod tests {
         assert_eq!(error.error_code(), ErrorCode::ValidationError);
         assert!(!error.is_retryable());
     }
+
+    #[test]
+    fn test_stellar_submission_errors() {
+        assert!(!AppError::from(StellarError::BadSequence).is_retryable());
+        assert!(AppError::from(StellarError::TransactionExpired).is_retryable());
+        assert!(AppError::from(StellarError::InsufficientFee).is_retryable());
+
+        let error = AppError::from(StellarError::transaction_pending("ab12"));
+        assert!(!error.is_retryable());
+        assert!(matches!(
+            &error.kind,
+            AppErrorKind::External(ExternalError::Blockchain { message, .. }) if message.contains("ab12")
+        ));
+        assert!(!AppError::from(StellarError::BadAuth).is_retryable());
+
+        let error = AppError::from(StellarError::InsufficientBalance);
+        assert_eq!(error.error_code(), ErrorCode::InsufficientAfriBalance);
+        assert!(!error.is_retryable());
+
+        let error = AppError::from(StellarError::DestinationNotFound);
+        assert_eq!(error.error_code(), ErrorCode::WalletNotFound);
+        assert!(error.user_message().contains("not found"));
+    }
 }
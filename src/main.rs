@@ -1,8 +1,6 @@
-mod chains;
-
-use chains::stellar::client::StellarClient;
-use chains::stellar::config::StellarConfig;
 use tracing::error;
+use Bitmesh_backend::chains::stellar::client::StellarClient;
+use Bitmesh_backend::chains::stellar::config::StellarConfig;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {